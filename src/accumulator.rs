@@ -0,0 +1,98 @@
+//! An accumulator two-phase channel.
+//! This is a wrapper around a directed two-phase channel,
+//! whose read-only `Data` accumulates the writable `Data` of all flushes instead of being overwritten.
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey,
+};
+
+/// A pointer to an accumulator channel, i.e. a directed channel whose read-only `Data` is a running total,
+/// into which the writable `Data` is folded on every flush.
+/// It can only be accessed using a [ChannelKey].
+///
+/// The readers and the writer of an accumulator channel are plain [`ReadOnlyDataPointer`]s and [`WritableDataPointer`]s.
+///
+/// This type should always be destroyed via the [AccumulatorChannel::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct AccumulatorChannel<Data> {
+    channel: DirectedChannelPointer<Data>,
+}
+
+impl<Data> AccumulatorChannel<Data> {
+    /// Create an accumulator channel with the given initial total and writable `Data`, and hand out three pointers to it.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn create(
+        total: Data,
+        writable: Data,
+    ) -> (Self, ReadOnlyDataPointer<Data>, WritableDataPointer<Data>) {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(total, writable);
+        (
+            Self {
+                channel: channel_pointer,
+            },
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Fold the writable `Data` into the total using the given closure.
+    /// The closure receives the total mutably as first argument, and the writable `Data` as second argument.
+    ///
+    /// See [`DirectedChannel::flush_with`].
+    pub fn flush_fold(&mut self, channel_key: &ChannelKey, combine: impl Fn(&mut Data, &Data)) {
+        self.channel.flush_with(channel_key, combine);
+    }
+
+    /// Destroys the accumulator channel linked with the given pointers (see [AccumulatorChannel::create]).
+    /// Returns the total and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        self.channel
+            .destroy(read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+impl<Data: Default> AccumulatorChannel<Data> {
+    /// Reset the total to `Data::default()`.
+    /// This counts as a flush, since the read-only `Data` changes.
+    pub fn reset_accumulator(&mut self, channel_key: &ChannelKey) {
+        self.channel
+            .flush_transform(channel_key, |_| Data::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{accumulator::AccumulatorChannel, MasterKey};
+
+    #[test]
+    fn test_fold_and_reset() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel, read_only_data_pointer, mut writable_data_pointer) =
+            AccumulatorChannel::create(0, 0);
+
+        for delta in [3, 5, -2] {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = delta;
+            channel.flush_fold(&master_key.get_channel_key(), |total, delta| {
+                *total += delta
+            });
+        }
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 6);
+
+        channel.reset_accumulator(&master_key.get_channel_key());
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 0);
+        assert_eq!(
+            channel.destroy([read_only_data_pointer], writable_data_pointer),
+            (0, -2)
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Directed two-phase channels allocated from a custom [`Allocator`], e.g. an arena, instead of the global allocator,
+//! and allocations that report failure instead of aborting.
+//!
+//! This needs a nightly compiler, since the allocator API is unstable.
+
+use std::{
+    alloc::{AllocError, Allocator},
+    ptr,
+};
+
+use crate::{
+    directed::{
+        debug_assert_live, register_live, DirectedChannel, DirectedChannelPointer,
+        IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer,
+    },
+    ChannelKey,
+};
+
+/// The pointers to a channel created via [`DirectedChannel::try_create`].
+pub type TryCreated<Data> = (
+    DirectedChannelPointer<Data>,
+    ReadOnlyDataPointer<Data>,
+    WritableDataPointer<Data>,
+);
+
+/// The pointers to a channel created via [`DirectedChannel::try_create_in`].
+pub type TryCreatedIn<Data, A> = (
+    DirectedChannelPointerIn<Data, A>,
+    ReadOnlyDataPointer<Data>,
+    WritableDataPointer<Data>,
+);
+
+/// A pointer to a directed channel that was allocated via [`DirectedChannel::create_in`].
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [DirectedChannel::destroy_in] or [DirectedChannelPointerIn::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct DirectedChannelPointerIn<Data, A: Allocator> {
+    channel: Box<DirectedChannel<Data>, A>,
+}
+
+impl<Data> DirectedChannel<Data> {
+    /// Create a directed channel in the given allocator and hand out three pointers to it.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn create_in<A: Allocator>(
+        alloc: A,
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        DirectedChannelPointerIn<Data, A>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        let mut channel = Box::new_in(Self::new(read_only, writable), alloc);
+        register_live((&*channel as *const Self).cast());
+        let read_only_data_pointer = channel.new_reader();
+        let writable_data_pointer = channel.writable_data_pointer();
+        (
+            DirectedChannelPointerIn { channel },
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Create a directed channel and hand out three pointers to it,
+    /// or return an error instead of aborting if the channel cannot be allocated.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn try_create(read_only: Data, writable: Data) -> Result<TryCreated<Data>, AllocError> {
+        let channel = Box::try_new(Self::new(read_only, writable))?;
+        Ok(Self::into_pointers(channel))
+    }
+
+    /// Create a directed channel in the given allocator and hand out three pointers to it,
+    /// or return an error instead of aborting if the allocator fails.
+    ///
+    /// See [`DirectedChannel::create_in`] for more info.
+    pub fn try_create_in<A: Allocator>(
+        alloc: A,
+        read_only: Data,
+        writable: Data,
+    ) -> Result<TryCreatedIn<Data, A>, AllocError> {
+        let mut channel = Box::try_new_in(Self::new(read_only, writable), alloc)?;
+        register_live((&*channel as *const Self).cast());
+        let read_only_data_pointer = channel.new_reader();
+        let writable_data_pointer = channel.writable_data_pointer();
+        Ok((
+            DirectedChannelPointerIn { channel },
+            read_only_data_pointer,
+            writable_data_pointer,
+        ))
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create_in]),
+    /// and returns its memory to the allocator.
+    /// Returns the read-only and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if fewer read-only data pointers are passed than were handed out (see [`DirectedChannelPointerIn::reader_count`]).
+    pub fn destroy_in<A: Allocator>(
+        channel_pointer: DirectedChannelPointerIn<Data, A>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        debug_assert_live(ptr::addr_of!(*channel_pointer.channel).cast());
+        let DirectedChannelPointerIn { mut channel } = channel_pointer;
+        if let Some(kind) = channel.check_destroy(read_only_data_pointers, &writable_data_pointer) {
+            panic!("{}", kind);
+        }
+
+        channel.close();
+        let channel = *channel;
+        (channel.read_only.0, channel.writable.0)
+    }
+}
+
+impl<Data: Clone, A: Allocator> DirectedChannelPointerIn<Data, A> {
+    /// Shorthand for [DirectedChannel::flush].
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        self.channel.flush(channel_key);
+    }
+}
+
+impl<Data, A: Allocator> DirectedChannelPointerIn<Data, A> {
+    /// Shorthand for [DirectedChannel::flush_count].
+    pub fn flush_count(&self) -> u64 {
+        self.channel.flush_count()
+    }
+
+    /// Create a new [`ReadOnlyDataPointer`] to the read-only `Data` field of this channel.
+    ///
+    /// See [`DirectedChannelPointer::new_reader`].
+    pub fn new_reader(&self) -> ReadOnlyDataPointer<Data> {
+        debug_assert_live(ptr::addr_of!(*self.channel).cast());
+        self.channel.new_reader()
+    }
+
+    /// The number of read-only data pointers handed out for this channel.
+    ///
+    /// See [`DirectedChannelPointer::reader_count`].
+    pub fn reader_count(&self) -> usize {
+        self.channel.reader_count()
+    }
+
+    /// Shorthand for [DirectedChannel::destroy_in].
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        DirectedChannel::destroy_in(self, read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+unsafe impl<Data: Send + Sync, A: Allocator + Send> Send for DirectedChannelPointerIn<Data, A> {}
+
+unsafe impl<Data: Send + Sync, A: Allocator + Sync> Sync for DirectedChannelPointerIn<Data, A> {}
+
+impl<Data: Clone + Send + Sync, A: Allocator + Send + Sync> IDirectedChannel
+    for DirectedChannelPointerIn<Data, A>
+{
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        DirectedChannelPointerIn::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        alloc::{AllocError, Allocator, Layout},
+        cell::{Cell, UnsafeCell},
+        ptr::NonNull,
+    };
+
+    use crate::{directed::DirectedChannel, MasterKey};
+
+    /// Hands out consecutive parts of a fixed buffer and never reuses freed memory.
+    #[repr(align(64))]
+    struct Bump {
+        memory: UnsafeCell<[u8; 4096]>,
+        offset: Cell<usize>,
+        allocations: Cell<usize>,
+    }
+
+    unsafe impl Allocator for &Bump {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let start = (self.offset.get() + layout.align() - 1) & !(layout.align() - 1);
+            let end = start + layout.size();
+            if layout.align() > 64 || end > 4096 {
+                return Err(AllocError);
+            }
+            self.offset.set(end);
+            self.allocations.set(self.allocations.get() + 1);
+            let data = unsafe { (self.memory.get() as *mut u8).add(start) };
+            Ok(NonNull::slice_from_raw_parts(
+                NonNull::new(data).unwrap(),
+                layout.size(),
+            ))
+        }
+
+        unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+    }
+
+    #[test]
+    fn test_bump_allocator() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let bump = Bump {
+            memory: UnsafeCell::new([0; 4096]),
+            offset: Cell::new(0),
+            allocations: Cell::new(0),
+        };
+
+        for i in 0..3 {
+            let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+                DirectedChannel::create_in(&bump, 0, 0);
+            let channel_address = read_only_data_pointer.as_ptr() as usize;
+            let bump_range = bump.memory.get() as usize..bump.memory.get() as usize + 4096;
+            assert!(bump_range.contains(&channel_address));
+
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = i;
+            channel_pointer.flush(&master_key.get_channel_key());
+            assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), i);
+            assert_eq!(
+                channel_pointer.destroy([read_only_data_pointer], writable_data_pointer),
+                (i, i)
+            );
+        }
+        assert_eq!(bump.allocations.get(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "only 1 of 2 read-only data pointers were returned")]
+    fn test_destroy_in_missing_readers() {
+        let bump = Bump {
+            memory: UnsafeCell::new([0; 4096]),
+            offset: Cell::new(0),
+            allocations: Cell::new(0),
+        };
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create_in(&bump, 0, 0);
+        assert_eq!(channel_pointer.reader_count(), 1);
+        let _ = channel_pointer.new_reader();
+        assert_eq!(channel_pointer.reader_count(), 2);
+        channel_pointer.destroy([read_only_data_pointer], writable_data_pointer);
+    }
+
+    #[test]
+    fn test_allocation_failure() {
+        let bump = Bump {
+            memory: UnsafeCell::new([0; 4096]),
+            offset: Cell::new(0),
+            allocations: Cell::new(0),
+        };
+        assert!(DirectedChannel::try_create_in(&bump, [0u8; 4096], [0u8; 4096]).is_err());
+        assert_eq!(bump.allocations.get(), 0);
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::try_create(1, 2).unwrap();
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 2);
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+}
@@ -0,0 +1,180 @@
+//! A directed two-phase channel whose read-only `Data` is shared via an [`Arc`].
+//! Readers can take cheap clones of the `Arc` and hold them across phases,
+//! without blocking the flushes of newer versions and without cloning the `Data` per reader.
+
+use std::{ptr::NonNull, sync::Arc};
+
+use crate::{
+    directed::{check_destroy, IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey,
+};
+
+/// A pointer to the read-only `Arc` of an Arc channel.
+pub type ArcDataPointer<Data> = ReadOnlyDataPointer<Arc<Data>>;
+
+/// A directed channel used for communication between threads, whose read-only `Data` is held in an [`Arc`].
+/// It holds the read-only `Arc<Data>` and a writable `Data`.
+///
+/// See [ArcDirectedChannel::create] for more info.
+#[derive(Debug)]
+pub struct ArcDirectedChannel<Data> {
+    read_only: Arc<Data>,
+    writable: Data,
+    version: u64,
+    dirty: bool,
+}
+
+/// A pointer to an Arc channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [ArcDirectedChannel::destroy] or [ArcDirectedChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct ArcDirectedChannelPointer<Data> {
+    channel: Box<ArcDirectedChannel<Data>>,
+}
+
+impl<Data> ArcDirectedChannel<Data> {
+    /// Create an Arc channel and hand out three pointers to it.
+    /// One [ArcDirectedChannelPointer] used to publish a clone of the writable `Data` as a new `Arc`,
+    /// one [ArcDataPointer] used to read from the channel, and
+    /// one [WritableDataPointer] used to write to the channel.
+    pub fn create(
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        ArcDirectedChannelPointer<Data>,
+        ArcDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        let mut channel_pointer = ArcDirectedChannelPointer {
+            channel: Box::new(ArcDirectedChannel {
+                read_only: Arc::new(read_only),
+                writable,
+                version: 0,
+                dirty: false,
+            }),
+        };
+        let channel: &mut ArcDirectedChannel<Data> = &mut channel_pointer.channel;
+        let read_only_data_pointer = ReadOnlyDataPointer {
+            data: NonNull::from(&channel.read_only),
+            version: (&channel.version) as *const u64,
+        };
+        let writable_data_pointer = WritableDataPointer {
+            data: NonNull::from(&mut channel.writable),
+            dirty: (&mut channel.dirty) as *mut bool,
+        };
+        (
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Destroys the Arc channel linked with the given pointers (see [ArcDirectedChannel::create]).
+    /// Returns the read-only `Arc`, which may still be shared with readers, and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: ArcDirectedChannelPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ArcDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Arc<Data>, Data) {
+        let ArcDirectedChannelPointer { channel } = channel_pointer;
+        if let Some(kind) = check_destroy(
+            (&channel.read_only as *const Arc<Data>).cast(),
+            (&channel.writable as *const Data).cast(),
+            0,
+            &mut read_only_data_pointers
+                .into_iter()
+                .map(|read_only_data_pointer| read_only_data_pointer.data.as_ptr() as *const u8),
+            writable_data_pointer.data.as_ptr() as *const u8,
+        ) {
+            panic!("{}", kind);
+        }
+
+        let ArcDirectedChannel {
+            read_only,
+            writable,
+            ..
+        } = *channel;
+        (read_only, writable)
+    }
+}
+
+impl<Data: Clone> ArcDirectedChannelPointer<Data> {
+    /// Replace the read-only `Arc` with a new `Arc` holding a clone of the writable `Data`.
+    /// Readers that hold a clone of the previous `Arc` keep seeing the previous `Data`.
+    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut ArcDirectedChannel<Data> = &mut self.channel;
+        channel.read_only = Arc::new(channel.writable.clone());
+        channel.version += 1;
+        channel.dirty = false;
+    }
+}
+
+impl<Data> ArcDirectedChannelPointer<Data> {
+    /// Shorthand for [ArcDirectedChannel::destroy].
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ArcDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Arc<Data>, Data) {
+        ArcDirectedChannel::destroy(self, read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+impl<Data> ReadOnlyDataPointer<Arc<Data>> {
+    /// Get a clone of the `Arc` pointed to by this pointer.
+    /// Unlike a reference obtained via [`ReadOnlyDataPointer::get`], the `Arc` can be held across phases.
+    pub fn snapshot_arc(&self, data_key: &DataKey) -> Arc<Data> {
+        self.get(data_key).clone()
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for ArcDirectedChannelPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for ArcDirectedChannelPointer<Data> {}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for ArcDirectedChannelPointer<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        ArcDirectedChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{arc::ArcDirectedChannel, MasterKey};
+
+    #[test]
+    fn test_readers_hold_different_generations() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer1, mut writable_data_pointer) =
+            ArcDirectedChannel::create(vec![0; 1000], vec![0; 1000]);
+        let read_only_data_pointer2 = read_only_data_pointer1;
+
+        writable_data_pointer.get_mut(&master_key.get_data_key())[0] = 1;
+        channel_pointer.flush(&master_key.get_channel_key());
+        let first = read_only_data_pointer1.snapshot_arc(&master_key.get_data_key());
+
+        writable_data_pointer.get_mut(&master_key.get_data_key())[0] = 2;
+        channel_pointer.flush(&master_key.get_channel_key());
+        let second = read_only_data_pointer2.snapshot_arc(&master_key.get_data_key());
+
+        assert_eq!(first[0], 1);
+        assert_eq!(second[0], 2);
+        assert!(Arc::ptr_eq(
+            &second,
+            &read_only_data_pointer1.snapshot_arc(&master_key.get_data_key())
+        ));
+
+        let (read_only, _) = channel_pointer.destroy(
+            [read_only_data_pointer1, read_only_data_pointer2],
+            writable_data_pointer,
+        );
+        assert!(Arc::ptr_eq(&read_only, &second));
+        assert_eq!(Arc::strong_count(&first), 1);
+    }
+}
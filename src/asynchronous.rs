@@ -0,0 +1,118 @@
+//! Flushing directed channels from async tasks.
+//! This module is only available with the `async` feature.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{directed::IDirectedChannel, ChannelKey};
+
+/// A boxed future returned by [`IDirectedChannelAsync::flush_async`].
+pub type FlushFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Object-safe trait for flushing channels from async tasks.
+///
+/// Every [`IDirectedChannel`] implements this trait by yielding to the executor once before flushing,
+/// such that other tasks get a chance to run before a long flush.
+/// To run the flush without blocking other tasks of the executor, wrap the channel into a [`BlockInPlace`].
+pub trait IDirectedChannelAsync: Send + Sync {
+    /// Perform the [`IDirectedChannel::flush`] operation asynchronously.
+    fn flush_async<'a>(&'a mut self, channel_key: &'a ChannelKey) -> FlushFuture<'a>;
+}
+
+impl<Channel: IDirectedChannel> IDirectedChannelAsync for Channel {
+    fn flush_async<'a>(&'a mut self, channel_key: &'a ChannelKey) -> FlushFuture<'a> {
+        Box::pin(async move {
+            YieldNow { yielded: false }.await;
+            self.flush(channel_key);
+        })
+    }
+}
+
+/// A channel whose async flush runs via [`tokio::task::block_in_place`],
+/// such that the executor moves its other tasks to different worker threads while the flush clones the `Data`.
+///
+/// **Panics** when flushed outside of a multi-threaded tokio runtime.
+#[derive(Debug)]
+pub struct BlockInPlace<Channel>(pub Channel);
+
+impl<Channel: IDirectedChannel> IDirectedChannelAsync for BlockInPlace<Channel> {
+    fn flush_async<'a>(&'a mut self, channel_key: &'a ChannelKey) -> FlushFuture<'a> {
+        Box::pin(async move {
+            tokio::task::block_in_place(|| self.0.flush(channel_key));
+        })
+    }
+}
+
+/// A future that returns pending once before it completes.
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            context.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        asynchronous::{BlockInPlace, IDirectedChannelAsync},
+        directed::DirectedChannel,
+        MasterKey,
+    };
+
+    #[tokio::test]
+    async fn test_flush_async() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        channel_pointer
+            .flush_async(&master_key.get_channel_key())
+            .await;
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 1);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_flush_block_in_place() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![], vec![]);
+        let mut channel_pointer = BlockInPlace(channel_pointer);
+
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .extend(0..1000);
+        let dyn_channel: &mut dyn IDirectedChannelAsync = &mut channel_pointer;
+        dyn_channel.flush_async(&master_key.get_channel_key()).await;
+        assert_eq!(
+            read_only_data_pointer.get(&master_key.get_data_key()).len(),
+            1000
+        );
+
+        DirectedChannel::destroy_single(
+            channel_pointer.0,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+}
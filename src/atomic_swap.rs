@@ -0,0 +1,229 @@
+//! A lock-free double buffer for a single writer and a single reader.
+//! Unlike the two-phase channels of this crate, it needs no keys:
+//! the writer publishes its buffer with an atomic pointer swap, and the reader loads the published buffer at any time.
+//! This trades the strict phase invariant for lock-freedom.
+
+use std::{cell::UnsafeCell, ptr};
+
+use crate::ordering::{hint, Arc, AtomicPtr, MemoryOrdering};
+
+/// A double buffer shared by an [AtomicSwapWriter] and an [AtomicSwapReader].
+///
+/// See [AtomicSwapChannel::create] for more info.
+#[derive(Debug)]
+pub struct AtomicSwapChannel<Data> {
+    buffers: [Box<UnsafeCell<Data>>; 2],
+    /// The buffer that the reader loads.
+    published: AtomicPtr<Data>,
+    /// The buffer that the reader is loading right now, or null.
+    reading: AtomicPtr<Data>,
+    ordering: MemoryOrdering,
+}
+
+/// The writing half of an atomic swap channel.
+/// It writes the back buffer and publishes it via [AtomicSwapWriter::publish].
+#[derive(Debug)]
+pub struct AtomicSwapWriter<Data> {
+    channel: Arc<AtomicSwapChannel<Data>>,
+    back: *mut Data,
+}
+
+/// The reading half of an atomic swap channel.
+/// It loads the buffer that was published last via [AtomicSwapReader::load].
+#[derive(Debug)]
+pub struct AtomicSwapReader<Data> {
+    channel: Arc<AtomicSwapChannel<Data>>,
+}
+
+impl<Data> AtomicSwapChannel<Data> {
+    /// Create an atomic swap channel and hand out its writer and its reader.
+    /// The reader loads the `published` `Data` until the writer publishes its `back` `Data`.
+    ///
+    /// The channel uses the default [`MemoryOrdering`], see [`AtomicSwapChannel::create_with_ordering`].
+    pub fn create(published: Data, back: Data) -> (AtomicSwapWriter<Data>, AtomicSwapReader<Data>) {
+        Self::create_with_ordering(published, back, MemoryOrdering::default())
+    }
+
+    /// Create an atomic swap channel whose atomic operations use the given [`MemoryOrdering`].
+    ///
+    /// See [`AtomicSwapChannel::create`] for more info.
+    pub fn create_with_ordering(
+        published: Data,
+        back: Data,
+        ordering: MemoryOrdering,
+    ) -> (AtomicSwapWriter<Data>, AtomicSwapReader<Data>) {
+        let buffers = [
+            Box::new(UnsafeCell::new(published)),
+            Box::new(UnsafeCell::new(back)),
+        ];
+        let back = buffers[1].get();
+        let channel = Arc::new(Self {
+            published: AtomicPtr::new(buffers[0].get()),
+            reading: AtomicPtr::new(ptr::null_mut()),
+            buffers,
+            ordering,
+        });
+        (
+            AtomicSwapWriter {
+                channel: channel.clone(),
+                back,
+            },
+            AtomicSwapReader { channel },
+        )
+    }
+
+    /// Destroys the atomic swap channel linked with the writer and the reader (see [AtomicSwapChannel::create]).
+    /// Returns the published and the back `Data`.
+    ///
+    /// **Panics** if the writer and the reader do not belong to the same channel.
+    pub fn destroy(writer: AtomicSwapWriter<Data>, reader: AtomicSwapReader<Data>) -> (Data, Data) {
+        assert!(Arc::ptr_eq(&writer.channel, &reader.channel));
+        let back_is_first = ptr::eq(writer.back, writer.channel.buffers[0].get());
+        drop(reader);
+        let channel = match Arc::try_unwrap(writer.channel) {
+            Ok(channel) => channel,
+            Err(_) => unreachable!("an atomic swap channel has only one writer and one reader"),
+        };
+        let [buffer1, buffer2] = channel.buffers;
+        let (buffer1, buffer2) = (buffer1.into_inner(), buffer2.into_inner());
+        if back_is_first {
+            (buffer2, buffer1)
+        } else {
+            (buffer1, buffer2)
+        }
+    }
+}
+
+impl<Data> AtomicSwapWriter<Data> {
+    /// Get a mutable reference to the back buffer.
+    ///
+    /// If the reader is still loading the back buffer because it was published before the last [AtomicSwapWriter::publish],
+    /// this spins until the reader is done.
+    /// Since the back buffer is not published, the reader cannot start loading it again.
+    pub fn write(&mut self) -> &mut Data {
+        let ordering = self.channel.ordering;
+        while ptr::eq(self.channel.reading.load(ordering.load()), self.back) {
+            hint::spin_loop();
+        }
+        ordering.acquire_fence();
+        unsafe { &mut *self.back }
+    }
+
+    /// Publish the back buffer, such that the reader loads it from now on.
+    /// The previously published buffer becomes the new back buffer.
+    /// Note that the new back buffer still contains the previously published `Data`, and not the `Data` published right now.
+    pub fn publish(&mut self) {
+        let ordering = self.channel.ordering;
+        ordering.release_fence();
+        self.back = self
+            .channel
+            .published
+            .swap(self.back, ordering.read_modify_write());
+        // Order the swap before the check in the next `write`, see `AtomicSwapReader::load`.
+        ordering.store_load_fence();
+    }
+}
+
+impl<Data: Clone> AtomicSwapReader<Data> {
+    /// Load a clone of the buffer that was published last.
+    /// This never waits for the writer, but retries if the writer publishes while the reader announces its load.
+    pub fn load(&mut self) -> Data {
+        let channel = &self.channel;
+        let ordering = channel.ordering;
+        let published = loop {
+            let published = channel.published.load(ordering.load());
+            channel.reading.store(published, ordering.store());
+            ordering.store_load_fence();
+            // If the writer did not publish since the first load, then it will see that this buffer is being read.
+            if ptr::eq(channel.published.load(ordering.load()), published) {
+                break published;
+            }
+        };
+        ordering.acquire_fence();
+        let data = unsafe { (*published).clone() };
+        ordering.release_fence();
+        channel.reading.store(ptr::null_mut(), ordering.store());
+        data
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for AtomicSwapWriter<Data> {}
+unsafe impl<Data: Send + Sync> Send for AtomicSwapReader<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for AtomicSwapWriter<Data> {}
+unsafe impl<Data: Send + Sync> Sync for AtomicSwapReader<Data> {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(loom))]
+    use std::thread;
+
+    use crate::{atomic_swap::AtomicSwapChannel, ordering::MemoryOrdering};
+
+    #[cfg(not(loom))]
+    #[test]
+    fn test_no_tearing() {
+        for ordering in [
+            MemoryOrdering::Relaxed,
+            MemoryOrdering::AcquireRelease,
+            MemoryOrdering::SeqCst,
+        ] {
+            let (mut writer, mut reader) =
+                AtomicSwapChannel::create_with_ordering([0u64; 16], [0u64; 16], ordering);
+
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    for i in 1..=100_000 {
+                        *writer.write() = [i; 16];
+                        writer.publish();
+                    }
+                });
+                scope.spawn(|| {
+                    let mut last = 0;
+                    while last < 100_000 {
+                        let data = reader.load();
+                        assert!(data.iter().all(|value| *value == data[0]), "torn read");
+                        assert!(data[0] >= last);
+                        last = data[0];
+                    }
+                });
+            });
+
+            assert_eq!(
+                AtomicSwapChannel::destroy(writer, reader),
+                ([100_000; 16], [99_999; 16])
+            );
+        }
+    }
+
+    /// Build with `cargo rustc --lib --profile test -- --cfg loom` and run the resulting test binary, see the seqlock loom test.
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_acquire_release() {
+        loom::model(|| {
+            let (mut writer, mut reader) = AtomicSwapChannel::create_with_ordering(
+                [0u64; 2],
+                [0u64; 2],
+                MemoryOrdering::AcquireRelease,
+            );
+            let thread = loom::thread::spawn(move || {
+                let mut last = 0;
+                for _ in 0..2 {
+                    let data = reader.load();
+                    assert_eq!(data[0], data[1], "torn read");
+                    assert!(data[0] >= last);
+                    last = data[0];
+                }
+                reader
+            });
+
+            for i in 1..=2 {
+                *writer.write() = [i; 2];
+                writer.publish();
+            }
+            let mut reader = thread.join().unwrap();
+            assert_eq!(reader.load(), [2; 2]);
+            assert_eq!(AtomicSwapChannel::destroy(writer, reader), ([2; 2], [1; 2]));
+        });
+    }
+}
@@ -3,8 +3,10 @@
 //! Each endpoint has an input and an output pointer,
 //! where the input of one endpoint is connected to the output of the other endpoint via a directed channel.
 
+use std::ptr::NonNull;
+
 use crate::{
-    directed::{DirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    directed::{DirectedChannel, IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
     ChannelKey, DataKey,
 };
 
@@ -57,28 +59,14 @@ impl<Data1, Data2> BidirectedChannel<Data1, Data2> {
     ) {
         let mut channel_pointer = BidirectedChannelPointer {
             channel: Box::new(BidirectedChannel {
-                channel1: DirectedChannel {
-                    read_only: read_only1,
-                    writable: writable1,
-                },
-                channel2: DirectedChannel {
-                    read_only: read_only2,
-                    writable: writable2,
-                },
+                channel1: DirectedChannel::new(read_only1, writable1),
+                channel2: DirectedChannel::new(read_only2, writable2),
             }),
         };
-        let input_data_pointer1 = ReadOnlyDataPointer {
-            data: (&channel_pointer.channel.channel1.read_only) as *const Data1,
-        };
-        let output_data_pointer1 = WritableDataPointer {
-            data: (&mut channel_pointer.channel.channel2.writable) as *mut Data2,
-        };
-        let input_data_pointer2 = ReadOnlyDataPointer {
-            data: (&channel_pointer.channel.channel2.read_only) as *const Data2,
-        };
-        let output_data_pointer2 = WritableDataPointer {
-            data: (&mut channel_pointer.channel.channel1.writable) as *mut Data1,
-        };
+        let input_data_pointer1 = channel_pointer.channel.channel1.read_only_data_pointer();
+        let output_data_pointer1 = channel_pointer.channel.channel2.writable_data_pointer();
+        let input_data_pointer2 = channel_pointer.channel.channel2.read_only_data_pointer();
+        let output_data_pointer2 = channel_pointer.channel.channel1.writable_data_pointer();
         (
             channel_pointer,
             BidirectedDataPointer {
@@ -102,18 +90,26 @@ impl<Data1, Data2> BidirectedChannel<Data1, Data2> {
     ) -> (Data1, Data1, Data2, Data2) {
         let BidirectedChannelPointer { mut channel } = channel_pointer;
         let BidirectedDataPointer {
-            input: ReadOnlyDataPointer { data: read_only1 },
-            output: WritableDataPointer { data: writable1 },
+            input: ReadOnlyDataPointer {
+                data: read_only1, ..
+            },
+            output: WritableDataPointer {
+                data: writable1, ..
+            },
         } = data_pointer1;
         let BidirectedDataPointer {
-            input: ReadOnlyDataPointer { data: read_only2 },
-            output: WritableDataPointer { data: writable2 },
+            input: ReadOnlyDataPointer {
+                data: read_only2, ..
+            },
+            output: WritableDataPointer {
+                data: writable2, ..
+            },
         } = data_pointer2;
 
-        let channel1_read_only = &channel.channel1.read_only as *const Data1;
-        let channel2_writable = &mut channel.channel1.writable as *mut Data1;
-        let channel2_read_only = &channel.channel2.read_only as *const Data2;
-        let channel1_writable = &mut channel.channel2.writable as *mut Data2;
+        let channel1_read_only = NonNull::from(&channel.channel1.read_only.0);
+        let channel2_writable = NonNull::from(&mut channel.channel1.writable.0);
+        let channel2_read_only = NonNull::from(&channel.channel2.read_only.0);
+        let channel1_writable = NonNull::from(&mut channel.channel2.writable.0);
 
         assert_eq!(channel1_read_only, read_only1);
         assert_eq!(channel1_writable, writable1);
@@ -121,10 +117,10 @@ impl<Data1, Data2> BidirectedChannel<Data1, Data2> {
         assert_eq!(channel2_writable, writable2);
 
         (
-            channel.channel1.read_only,
-            channel.channel1.writable,
-            channel.channel2.read_only,
-            channel.channel2.writable,
+            channel.channel1.read_only.0,
+            channel.channel1.writable.0,
+            channel.channel2.read_only.0,
+            channel.channel2.writable.0,
         )
     }
 }
@@ -151,6 +147,13 @@ impl<Data1: Clone, Data2: Clone> BidirectedChannelPointer<Data1, Data2> {
         DirectedChannel::flush(&mut self.channel.channel1, key);
         self.channel.channel2.flush(key);
     }
+
+    /// Clone the writable `Data`s of both directions into their read-only `Data`s.
+    ///
+    /// Equivalent to [`BidirectedChannelPointer::flush`], but makes explicit that both directions are flushed.
+    pub fn flush_both(&mut self, channel_key: &ChannelKey) {
+        self.flush(channel_key);
+    }
 }
 
 impl<Data1, Data2> BidirectedChannelPointer<Data1, Data2> {
@@ -176,11 +179,17 @@ impl<Input, Output> BidirectedDataPointer<Input, Output> {
     }
 }
 
-unsafe impl<Data1, Data2> Send for BidirectedChannelPointer<Data1, Data2> {}
-unsafe impl<Input, Output> Send for BidirectedDataPointer<Input, Output> {}
+unsafe impl<Data1: Send + Sync, Data2: Send + Sync> Send
+    for BidirectedChannelPointer<Data1, Data2>
+{
+}
+unsafe impl<Input: Send + Sync, Output: Send + Sync> Send for BidirectedDataPointer<Input, Output> {}
 
-unsafe impl<Data1, Data2> Sync for BidirectedChannelPointer<Data1, Data2> {}
-unsafe impl<Input, Output> Sync for BidirectedDataPointer<Input, Output> {}
+unsafe impl<Data1: Send + Sync, Data2: Send + Sync> Sync
+    for BidirectedChannelPointer<Data1, Data2>
+{
+}
+unsafe impl<Input: Send + Sync, Output: Send + Sync> Sync for BidirectedDataPointer<Input, Output> {}
 
 /// Object-safe trait for [`BidirectedChannelPointer`]s.
 pub trait IBidirectedChannel: Send + Sync {
@@ -188,16 +197,28 @@ pub trait IBidirectedChannel: Send + Sync {
     fn flush(&mut self, channel_key: &ChannelKey);
 }
 
-impl<Data1: Clone, Data2: Clone> IBidirectedChannel for BidirectedChannelPointer<Data1, Data2> {
+impl<Data1: Clone + Send + Sync, Data2: Clone + Send + Sync> IBidirectedChannel
+    for BidirectedChannelPointer<Data1, Data2>
+{
     fn flush(&mut self, channel_key: &ChannelKey) {
         BidirectedChannelPointer::flush(self, channel_key);
     }
 }
 
+/// A bidirected channel can be used wherever a directed channel is flushed, since it flushes both its directions.
+impl<Data1: Clone + Send + Sync, Data2: Clone + Send + Sync> IDirectedChannel
+    for BidirectedChannelPointer<Data1, Data2>
+{
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        BidirectedChannelPointer::flush_both(self, channel_key);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         bidirected::{BidirectedChannel, IBidirectedChannel},
+        directed::IDirectedChannel,
         MasterKey,
     };
 
@@ -227,6 +248,31 @@ mod tests {
         assert_eq!(writable_data2, 7);
     }
 
+    #[test]
+    fn test_ping_pong() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut data_pointer1, mut data_pointer2) =
+            BidirectedChannel::create_equal(String::new(), 0);
+
+        for i in 0..3 {
+            let data_key = master_key.get_data_key();
+            assert_eq!(*data_pointer1.get_input(&data_key), "ping".repeat(i));
+            *data_pointer1.get_output(&data_key) = i + 1;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush_both(&channel_key);
+
+            let data_key = channel_key.into_data_key();
+            assert_eq!(*data_pointer2.get_input(&data_key), i + 1);
+            *data_pointer2.get_output(&data_key) = "ping".repeat(i + 1);
+
+            let channel_key = data_key.into_channel_key();
+            IDirectedChannel::flush(&mut channel_pointer, &channel_key);
+        }
+
+        BidirectedChannel::destroy(channel_pointer, data_pointer1, data_pointer2);
+    }
+
     #[test]
     fn ensure_channel_is_object_safe() {
         let mut master_key = unsafe { MasterKey::create_unlimited() };
@@ -0,0 +1,301 @@
+//! A broadcast two-phase channel.
+//! The channel provides one writable data pointer and multiple read-only data pointers,
+//! each of which points to its own copy of the data.
+//! Data is transmitted from the writable end to each of the read-only ends, either to all at once or to each individually.
+
+use std::ptr::{self, NonNull};
+
+use crate::{
+    directed::{IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey,
+};
+
+/// A broadcast channel used for communication between threads.
+/// It holds one writable instance of `Data` and multiple read-only instances of `Data`.
+/// A flush copies the writable data into all or some of the read-only data.
+///
+/// This allows a slow reader to keep its `Data` stable while other readers receive updates.
+///
+/// See [BroadcastChannel::create] for more info.
+#[derive(Debug)]
+pub struct BroadcastChannel<Data> {
+    read_only: Vec<Data>,
+    versions: Vec<u64>,
+    writable: Data,
+    dirty: bool,
+}
+
+/// A pointer to a broadcast channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [BroadcastChannel::destroy] or [BroadcastChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct BroadcastChannelPointer<Data> {
+    channel: Box<BroadcastChannel<Data>>,
+}
+
+impl<Data: Clone> BroadcastChannel<Data> {
+    /// Create a broadcast channel with `n` readers and hand out pointers to it.
+    /// One [BroadcastChannelPointer] used to flush (copy) the content of the writable `Data` field into the read-only data fields,
+    /// one [WritableDataPointer] used to write to the broadcast channel, and
+    /// `n` [ReadOnlyDataPointer]s used to read from the broadcast channel, each pointing to its own read-only `Data` field.
+    ///
+    /// All read-only `Data` fields are initialised as clones of the writable `Data`.
+    pub fn create(
+        writable: Data,
+        n: usize,
+    ) -> (
+        BroadcastChannelPointer<Data>,
+        WritableDataPointer<Data>,
+        Vec<ReadOnlyDataPointer<Data>>,
+    ) {
+        let mut channel_pointer = BroadcastChannelPointer {
+            channel: Box::new(BroadcastChannel {
+                read_only: vec![writable.clone(); n],
+                versions: vec![0; n],
+                writable,
+                dirty: false,
+            }),
+        };
+        let channel: &mut BroadcastChannel<Data> = &mut channel_pointer.channel;
+        let writable_data_pointer = WritableDataPointer {
+            data: NonNull::from(&mut channel.writable),
+            dirty: (&mut channel.dirty) as *mut bool,
+        };
+        let read_only_data_pointers = channel
+            .read_only
+            .iter()
+            .zip(&channel.versions)
+            .map(|(read_only, version)| ReadOnlyDataPointer {
+                data: NonNull::from(read_only),
+                version: version as *const u64,
+            })
+            .collect();
+        (
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        )
+    }
+}
+
+impl<Data> BroadcastChannel<Data> {
+    /// Destroys the broadcast channel linked with the given pointers (see [BroadcastChannel::create]).
+    /// Returns the writable `Data` and the read-only `Data`s in the order of the [ReadOnlyDataPointer]s returned by [BroadcastChannel::create].
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: BroadcastChannelPointer<Data>,
+        writable_data_pointer: WritableDataPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+    ) -> (Data, Vec<Data>) {
+        let BroadcastChannelPointer { mut channel } = channel_pointer;
+        let channel_writable_data_pointer = NonNull::from(&mut channel.writable);
+        let WritableDataPointer {
+            data: writable_data_pointer,
+            ..
+        } = writable_data_pointer;
+        assert_eq!(channel_writable_data_pointer, writable_data_pointer);
+
+        for read_only_data_pointer in read_only_data_pointers {
+            let ReadOnlyDataPointer {
+                data: read_only_data_pointer,
+                ..
+            } = read_only_data_pointer;
+            assert!(channel
+                .read_only
+                .iter()
+                .any(|read_only| ptr::eq(read_only, read_only_data_pointer.as_ptr())));
+        }
+
+        (channel.writable, channel.read_only)
+    }
+}
+
+impl<Data: Clone> BroadcastChannelPointer<Data> {
+    /// Clone the writable `Data` into all read-only `Data`s.
+    /// All clones are assigned only once they are complete, so if [`Clone::clone`] panics, all read-only `Data`s stay unchanged.
+    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut BroadcastChannel<Data> = &mut self.channel;
+        let clones: Vec<_> = channel
+            .read_only
+            .iter()
+            .map(|_| channel.writable.clone())
+            .collect();
+        for ((read_only, version), clone) in channel
+            .read_only
+            .iter_mut()
+            .zip(&mut channel.versions)
+            .zip(clones)
+        {
+            *read_only = clone;
+            *version += 1;
+        }
+        channel.dirty = false;
+    }
+
+    /// Clone the writable `Data` into the read-only `Data` of the reader with the given index.
+    /// The index refers to the order of the [ReadOnlyDataPointer]s returned by [BroadcastChannel::create].
+    ///
+    /// **Panics** if the index is out of bounds.
+    pub fn flush_reader(&mut self, index: usize, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut BroadcastChannel<Data> = &mut self.channel;
+        channel.read_only[index] = channel.writable.clone();
+        channel.versions[index] += 1;
+    }
+}
+
+impl<Data> BroadcastChannelPointer<Data> {
+    /// The number of readers of this broadcast channel.
+    pub fn reader_count(&self) -> usize {
+        self.channel.read_only.len()
+    }
+
+    /// Shorthand for [BroadcastChannel::destroy].
+    pub fn destroy(
+        self,
+        writable_data_pointer: WritableDataPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+    ) -> (Data, Vec<Data>) {
+        BroadcastChannel::destroy(self, writable_data_pointer, read_only_data_pointers)
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for BroadcastChannelPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for BroadcastChannelPointer<Data> {}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for BroadcastChannelPointer<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        BroadcastChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    use crate::{broadcast::BroadcastChannel, directed::IDirectedChannel, MasterKey};
+
+    /// A type whose clone panics once the shared number of remaining clones is used up.
+    #[derive(Debug)]
+    struct LimitedClone {
+        value: i32,
+        remaining_clones: Rc<Cell<usize>>,
+    }
+
+    impl Clone for LimitedClone {
+        fn clone(&self) -> Self {
+            let remaining_clones = self.remaining_clones.get();
+            assert!(remaining_clones > 0, "no clones remaining");
+            self.remaining_clones.set(remaining_clones - 1);
+            Self {
+                value: self.value,
+                remaining_clones: self.remaining_clones.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut writable_data_pointer, read_only_data_pointers) =
+            BroadcastChannel::create(0, 3);
+        assert_eq!(channel_pointer.reader_count(), 3);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        channel_pointer.flush(&master_key.get_channel_key());
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        channel_pointer.flush_reader(1, &master_key.get_channel_key());
+        channel_pointer.flush_reader(2, &master_key.get_channel_key());
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 3;
+        channel_pointer.flush_reader(2, &master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        let values: Vec<_> = read_only_data_pointers
+            .iter()
+            .map(|pointer| *pointer.get(&data_key))
+            .collect();
+        assert_eq!(values, [1, 2, 3]);
+        let versions: Vec<_> = read_only_data_pointers
+            .iter()
+            .map(|pointer| pointer.version(&data_key))
+            .collect();
+        assert_eq!(versions, [1, 2, 3]);
+
+        let (writable_data, read_only_data) = BroadcastChannel::destroy(
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        );
+        assert_eq!(writable_data, 3);
+        assert_eq!(read_only_data, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_panicking_clone_keeps_all_readers() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let remaining_clones = Rc::new(Cell::new(usize::MAX));
+        let (mut channel_pointer, mut writable_data_pointer, read_only_data_pointers) =
+            BroadcastChannel::create(
+                LimitedClone {
+                    value: 0,
+                    remaining_clones: remaining_clones.clone(),
+                },
+                3,
+            );
+
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .value = 1;
+        remaining_clones.set(2);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            channel_pointer.flush(&master_key.get_channel_key())
+        }));
+        assert!(result.is_err());
+
+        let data_key = master_key.get_data_key();
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(read_only_data_pointer.get(&data_key).value, 0);
+            assert_eq!(read_only_data_pointer.version(&data_key), 0);
+        }
+
+        remaining_clones.set(usize::MAX);
+        channel_pointer.flush(&data_key.into_channel_key());
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(
+                read_only_data_pointer.get(&master_key.get_data_key()).value,
+                1
+            );
+        }
+        BroadcastChannel::destroy(
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        );
+    }
+
+    #[test]
+    fn ensure_channel_is_object_safe() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut writable_data_pointer, read_only_data_pointers) =
+            BroadcastChannel::create(1, 2);
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        let dyn_channel_pointer: &mut dyn IDirectedChannel = &mut channel_pointer;
+
+        dyn_channel_pointer.flush(&master_key.get_channel_key());
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 2);
+        }
+        BroadcastChannel::destroy(
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! A builder for directed two-phase channels with optional features,
+//! as an alternative to the positional constructors of [`DirectedChannel`].
+
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+use crate::directed::{
+    DirectedChannel, DirectedChannelPointer, Observer, ReadOnlyDataPointer, WritableDataPointer,
+    UNVERSIONED,
+};
+
+/// The error returned by [`DirectedChannelBuilder::build`] if not both `Data` fields were set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// The read-only `Data` was not set via [`DirectedChannelBuilder::read_only`].
+    MissingReadOnly,
+    /// The writable `Data` was not set via [`DirectedChannelBuilder::writable`].
+    MissingWritable,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingReadOnly => write!(f, "the read-only data was not set"),
+            BuildError::MissingWritable => write!(f, "the writable data was not set"),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// The pointers to a channel built via [`DirectedChannelBuilder::build`].
+pub type BuiltChannel<Data> = (
+    DirectedChannelPointer<Data>,
+    Vec<ReadOnlyDataPointer<Data>>,
+    WritableDataPointer<Data>,
+);
+
+/// A builder for a [`DirectedChannel`].
+///
+/// By default, the channel hands out one read-only data pointer, which does not report versions.
+#[must_use]
+pub struct DirectedChannelBuilder<Data> {
+    read_only: Option<Data>,
+    writable: Option<Data>,
+    readers: usize,
+    versioned: bool,
+    observers: Vec<Observer<Data>>,
+}
+
+impl<Data> Default for DirectedChannelBuilder<Data> {
+    fn default() -> Self {
+        Self {
+            read_only: None,
+            writable: None,
+            readers: 1,
+            versioned: false,
+            observers: Vec::new(),
+        }
+    }
+}
+
+impl<Data> DirectedChannelBuilder<Data> {
+    /// Create a builder without any `Data`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the initial read-only `Data`.
+    pub fn read_only(mut self, read_only: Data) -> Self {
+        self.read_only = Some(read_only);
+        self
+    }
+
+    /// Set the initial writable `Data`.
+    pub fn writable(mut self, writable: Data) -> Self {
+        self.writable = Some(writable);
+        self
+    }
+
+    /// Set the number of read-only data pointers handed out by [`DirectedChannelBuilder::build`].
+    ///
+    /// See [`DirectedChannel::create_with_readers`].
+    pub fn readers(mut self, readers: usize) -> Self {
+        self.readers = readers;
+        self
+    }
+
+    /// Let the read-only data pointers report the [version](ReadOnlyDataPointer::version) of the read-only `Data`.
+    /// Otherwise, their version is always zero.
+    pub fn with_version(mut self) -> Self {
+        self.versioned = true;
+        self
+    }
+
+    /// Register an observer of the flushes of the channel.
+    ///
+    /// See [`DirectedChannelPointer::on_flush`].
+    pub fn on_flush(mut self, observer: Observer<Data>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Create the directed channel and hand out its channel pointer, its read-only data pointers and its writable data pointer.
+    ///
+    /// Returns an error if the read-only or the writable `Data` was not set.
+    pub fn build(self) -> Result<BuiltChannel<Data>, BuildError> {
+        let read_only = self.read_only.ok_or(BuildError::MissingReadOnly)?;
+        let writable = self.writable.ok_or(BuildError::MissingWritable)?;
+        let (mut channel_pointer, mut read_only_data_pointers, writable_data_pointer) =
+            DirectedChannel::create_with_readers(read_only, writable, self.readers);
+        if !self.versioned {
+            for read_only_data_pointer in &mut read_only_data_pointers {
+                read_only_data_pointer.version = (&UNVERSIONED) as *const u64;
+            }
+        }
+        for observer in self.observers {
+            channel_pointer.on_flush(observer);
+        }
+        Ok((
+            channel_pointer,
+            read_only_data_pointers,
+            writable_data_pointer,
+        ))
+    }
+}
+
+impl<Data: Debug> Debug for DirectedChannelBuilder<Data> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("DirectedChannelBuilder")
+            .field("read_only", &self.read_only)
+            .field("writable", &self.writable)
+            .field("readers", &self.readers)
+            .field("versioned", &self.versioned)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use crate::{
+        builder::{BuildError, DirectedChannelBuilder},
+        MasterKey,
+    };
+
+    #[test]
+    fn test_build() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let flushes = Arc::new(AtomicUsize::new(0));
+        let (mut channel_pointer, read_only_data_pointers, mut writable_data_pointer) =
+            DirectedChannelBuilder::new()
+                .read_only(0)
+                .writable(0)
+                .readers(2)
+                .with_version()
+                .on_flush(Box::new({
+                    let flushes = flushes.clone();
+                    move |_: &i32| {
+                        flushes.fetch_add(1, Ordering::Relaxed);
+                    }
+                }))
+                .build()
+                .unwrap();
+        assert_eq!(read_only_data_pointers.len(), 2);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 5;
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(flushes.load(Ordering::Relaxed), 1);
+        let data_key = master_key.get_data_key();
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&data_key), 5);
+            assert_eq!(read_only_data_pointer.version(&data_key), 1);
+        }
+
+        channel_pointer.destroy(read_only_data_pointers, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_build_without_version() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointers, writable_data_pointer) =
+            DirectedChannelBuilder::new()
+                .read_only(0)
+                .writable(1)
+                .build()
+                .unwrap();
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            read_only_data_pointers[0].version(&master_key.get_data_key()),
+            0
+        );
+        channel_pointer.destroy(read_only_data_pointers, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_missing_data() {
+        assert_eq!(
+            DirectedChannelBuilder::new().read_only(0).build().err(),
+            Some(BuildError::MissingWritable)
+        );
+        assert_eq!(
+            DirectedChannelBuilder::new().writable(0).build().err(),
+            Some(BuildError::MissingReadOnly)
+        );
+    }
+}
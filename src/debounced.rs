@@ -0,0 +1,103 @@
+//! Debounced flushing of directed channels.
+//! A writer that updates its `Data` rapidly does not need to publish every change if the readers only sample periodically,
+//! so the writes are collapsed into at most one flush per interval.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    directed::{DirectedChannelPointer, IDirectedChannel},
+    ChannelKey,
+};
+
+/// Wraps a directed channel and flushes it at most once per [`DebouncedFlusher::min_interval`],
+/// and only if its writable `Data` was accessed mutably since the last flush.
+///
+/// Writes happen through the [`WritableDataPointer`](crate::directed::WritableDataPointer) of the channel as usual,
+/// which marks the channel as pending (see [`DirectedChannelPointer::flush_dirty`]).
+#[derive(Debug)]
+#[must_use]
+pub struct DebouncedFlusher<Data> {
+    channel: DirectedChannelPointer<Data>,
+    min_interval: Duration,
+    last_flush: Option<Instant>,
+}
+
+impl<Data> DebouncedFlusher<Data> {
+    /// Wrap the given channel, such that it is flushed at most once per `min_interval`.
+    pub fn new(channel: DirectedChannelPointer<Data>, min_interval: Duration) -> Self {
+        Self {
+            channel,
+            min_interval,
+            last_flush: None,
+        }
+    }
+
+    /// The minimum interval between two flushes.
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// The wrapped channel.
+    pub fn channel(&self) -> &DirectedChannelPointer<Data> {
+        &self.channel
+    }
+
+    /// Unwrap the channel, e.g. to destroy it.
+    pub fn into_inner(self) -> DirectedChannelPointer<Data> {
+        self.channel
+    }
+}
+
+impl<Data: Clone> DebouncedFlusher<Data> {
+    /// Flush the channel if there are pending writes, and if the last flush was at least [`DebouncedFlusher::min_interval`] ago.
+    /// Returns `true` if the channel was flushed.
+    pub fn tick(&mut self, channel_key: &ChannelKey) -> bool {
+        let due = self
+            .last_flush
+            .map_or(true, |last_flush| last_flush.elapsed() >= self.min_interval);
+        if due && self.channel.flush_dirty(channel_key) {
+            self.last_flush = Some(Instant::now());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for DebouncedFlusher<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        self.tick(channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{debounced::DebouncedFlusher, directed::DirectedChannel, MasterKey};
+
+    #[test]
+    fn test_collapse_writes() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let mut flusher = DebouncedFlusher::new(channel_pointer, Duration::from_secs(3600));
+        assert!(!flusher.tick(&master_key.get_channel_key()));
+
+        for value in 1..=5 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = value;
+        }
+        assert!(flusher.tick(&master_key.get_channel_key()));
+        assert_eq!(flusher.channel().flush_count(), 1);
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 5);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 6;
+        assert!(!flusher.tick(&master_key.get_channel_key()));
+        assert_eq!(flusher.channel().flush_count(), 1);
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 5);
+
+        flusher
+            .into_inner()
+            .destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+}
@@ -0,0 +1,264 @@
+//! A delta two-phase channel.
+//! The writer writes a full `Data`, but on flush only the difference to the previously flushed `Data` is computed,
+//! such that readers can keep their own copy of the `Data` up to date by applying the deltas.
+
+use std::ptr::{self, NonNull};
+
+use crate::{
+    directed::{IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey,
+};
+
+/// A type that can compute the difference `D` between two of its instances, and apply such a difference to itself.
+///
+/// Implementations must ensure that applying `Self::diff(old, new)` to a copy of `old` results in a copy of `new`.
+pub trait Diff<D> {
+    /// Compute the difference that transforms `old` into `new`.
+    fn diff(old: &Self, new: &Self) -> D;
+
+    /// Apply a difference computed by [`Diff::diff`] to `self`.
+    fn apply(&mut self, delta: &D);
+}
+
+/// The read-only side of a delta channel.
+/// It holds the last flushed full `Data` as well as the delta of the last flush.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delta<Data, D> {
+    value: Data,
+    delta: Option<D>,
+}
+
+/// A pointer to the read-only [`Delta`] of a delta channel.
+pub type DeltaDataPointer<Data, D> = ReadOnlyDataPointer<Delta<Data, D>>;
+
+/// A delta channel used for communication between threads.
+/// It holds a [`Delta`] and a writable `Data`.
+///
+/// See [DeltaChannel::create] for more info.
+#[derive(Debug)]
+pub struct DeltaChannel<Data, D> {
+    read_only: Delta<Data, D>,
+    writable: Data,
+    version: u64,
+    dirty: bool,
+}
+
+/// A pointer to a delta channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [DeltaChannel::destroy] or [DeltaChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct DeltaChannelPointer<Data, D> {
+    channel: Box<DeltaChannel<Data, D>>,
+}
+
+impl<Data, D> Delta<Data, D> {
+    /// The last flushed full `Data`.
+    pub fn value(&self) -> &Data {
+        &self.value
+    }
+
+    /// The delta computed by the last flush, or `None` if the channel was not flushed yet.
+    pub fn delta(&self) -> Option<&D> {
+        self.delta.as_ref()
+    }
+}
+
+impl<Data: Diff<D>, D> Delta<Data, D> {
+    /// Apply the delta of the last flush to the given copy of the `Data`, if there is one.
+    ///
+    /// This only brings the copy up to date if it was equal to the `Data` before the last flush.
+    /// A reader that may miss flushes should compare the [`ReadOnlyDataPointer::version`] with the version of its copy,
+    /// and clone the full [`Delta::value`] instead if it missed more than one flush.
+    pub fn apply_to(&self, copy: &mut Data) {
+        if let Some(delta) = &self.delta {
+            copy.apply(delta);
+        }
+    }
+}
+
+impl<Data, D> DeltaChannel<Data, D> {
+    /// Create a delta channel and hand out three pointers to it.
+    /// One [DeltaChannelPointer] used to flush the difference of the writable `Data` to the read-only `Data`,
+    /// one [DeltaDataPointer] used to read the [`Delta`] from the channel, and
+    /// one [WritableDataPointer] used to write the full `Data` to the channel.
+    pub fn create(
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        DeltaChannelPointer<Data, D>,
+        DeltaDataPointer<Data, D>,
+        WritableDataPointer<Data>,
+    ) {
+        let mut channel_pointer = DeltaChannelPointer {
+            channel: Box::new(DeltaChannel {
+                read_only: Delta {
+                    value: read_only,
+                    delta: None,
+                },
+                writable,
+                version: 0,
+                dirty: false,
+            }),
+        };
+        let channel: &mut DeltaChannel<Data, D> = &mut channel_pointer.channel;
+        let read_only_data_pointer = ReadOnlyDataPointer {
+            data: NonNull::from(&channel.read_only),
+            version: (&channel.version) as *const u64,
+        };
+        let writable_data_pointer = WritableDataPointer {
+            data: NonNull::from(&mut channel.writable),
+            dirty: (&mut channel.dirty) as *mut bool,
+        };
+        (
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Destroys the delta channel linked with the given pointers (see [DeltaChannel::create]).
+    /// Returns the last flushed full `Data` and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: DeltaChannelPointer<Data, D>,
+        read_only_data_pointers: impl IntoIterator<Item = DeltaDataPointer<Data, D>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        let DeltaChannelPointer { channel } = channel_pointer;
+        assert!(ptr::eq(
+            &channel.writable,
+            writable_data_pointer.data.as_ptr()
+        ));
+        for read_only_data_pointer in read_only_data_pointers {
+            assert!(ptr::eq(
+                &channel.read_only,
+                read_only_data_pointer.data.as_ptr()
+            ));
+        }
+
+        let DeltaChannel {
+            read_only,
+            writable,
+            ..
+        } = *channel;
+        (read_only.value, writable)
+    }
+}
+
+impl<Data: Diff<D>, D> DeltaChannelPointer<Data, D> {
+    /// Compute the difference between the last flushed `Data` and the writable `Data`,
+    /// store it as the delta of the read-only [`Delta`], and apply it to the full value.
+    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut DeltaChannel<Data, D> = &mut self.channel;
+        let delta = Data::diff(&channel.read_only.value, &channel.writable);
+        channel.read_only.value.apply(&delta);
+        channel.read_only.delta = Some(delta);
+        channel.version += 1;
+        channel.dirty = false;
+    }
+}
+
+impl<Data, D> DeltaChannelPointer<Data, D> {
+    /// Shorthand for [DeltaChannel::destroy].
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = DeltaDataPointer<Data, D>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        DeltaChannel::destroy(self, read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+unsafe impl<Data: Send + Sync, D: Send + Sync> Send for DeltaChannelPointer<Data, D> {}
+
+unsafe impl<Data: Send + Sync, D: Send + Sync> Sync for DeltaChannelPointer<Data, D> {}
+
+impl<Data: Diff<D> + Send + Sync, D: Send + Sync> IDirectedChannel
+    for DeltaChannelPointer<Data, D>
+{
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        DeltaChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        delta::{DeltaChannel, Diff},
+        MasterKey,
+    };
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    struct State {
+        position: (i32, i32),
+        name: String,
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct StateDelta {
+        position: Option<(i32, i32)>,
+        name: Option<String>,
+    }
+
+    impl Diff<StateDelta> for State {
+        fn diff(old: &Self, new: &Self) -> StateDelta {
+            StateDelta {
+                position: (old.position != new.position).then(|| new.position),
+                name: (old.name != new.name).then(|| new.name.clone()),
+            }
+        }
+
+        fn apply(&mut self, delta: &StateDelta) {
+            if let Some(position) = delta.position {
+                self.position = position;
+            }
+            if let Some(name) = &delta.name {
+                self.name.clone_from(name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_delta_of_single_field() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let initial = State {
+            position: (0, 0),
+            name: "player".to_string(),
+        };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DeltaChannel::create(initial.clone(), initial.clone());
+        let mut copy = initial;
+        assert_eq!(
+            read_only_data_pointer
+                .get(&master_key.get_data_key())
+                .delta(),
+            None
+        );
+
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .position = (3, 4);
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        let delta = read_only_data_pointer.get(&data_key);
+        assert_eq!(
+            delta.delta(),
+            Some(&StateDelta {
+                position: Some((3, 4)),
+                name: None,
+            })
+        );
+        delta.apply_to(&mut copy);
+        assert_eq!(&copy, delta.value());
+        assert_eq!(&copy, writable_data_pointer.get(&data_key));
+
+        let (read_only, writable) =
+            channel_pointer.destroy([read_only_data_pointer], writable_data_pointer);
+        assert_eq!(read_only, copy);
+        assert_eq!(writable, copy);
+    }
+}
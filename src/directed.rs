@@ -2,8 +2,96 @@
 //! The channel provides two data pointers, one of which is read-only.
 //! Data is only transmitted from the writable end to the readable end.
 
+use std::{
+    alloc::{self, Layout},
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    iter,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(debug_assertions)]
+use std::{
+    collections::BTreeSet,
+    sync::{atomic::AtomicPtr, Mutex, PoisonError},
+};
+
+#[cfg(feature = "futures")]
+use std::sync::Arc;
+#[cfg(feature = "stats")]
+use std::time::Instant;
+
+#[cfg(feature = "futures")]
+use crate::stream::FlushNotifier;
 use crate::{ChannelKey, DataKey};
 
+/// The version reported by read-only pointers to `Data` that is not versioned.
+pub(crate) static UNVERSIONED: u64 = 0;
+
+/// The addresses of the channels that were handed out and not destroyed yet.
+/// In debug builds, this detects the use of a pointer to a destroyed channel without reading the freed channel.
+///
+/// The set is allocated on first use, since `Mutex::new` is not `const` in the minimum supported Rust version.
+#[cfg(debug_assertions)]
+fn live_channels() -> &'static Mutex<BTreeSet<usize>> {
+    static LIVE_CHANNELS: AtomicPtr<Mutex<BTreeSet<usize>>> = AtomicPtr::new(ptr::null_mut());
+    let mut live_channels = LIVE_CHANNELS.load(Ordering::Acquire);
+    if live_channels.is_null() {
+        let new = Box::into_raw(Box::new(Mutex::new(BTreeSet::new())));
+        live_channels = match LIVE_CHANNELS.compare_exchange(
+            ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new,
+            Err(current) => {
+                // Another thread allocated the set first, and `new` was never shared.
+                drop(unsafe { Box::from_raw(new) });
+                current
+            }
+        };
+    }
+    // The set is never freed.
+    unsafe { &*live_channels }
+}
+
+/// Record that the channel at the given address was handed out, see [`live_channels`].
+pub(crate) fn register_live(#[allow(unused)] channel: *const u8) {
+    #[cfg(debug_assertions)]
+    live_channels()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(channel as usize);
+}
+
+/// Record that the channel at the given address was destroyed, see [`live_channels`].
+fn unregister_live(#[allow(unused)] channel: *const u8) {
+    #[cfg(debug_assertions)]
+    live_channels()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&(channel as usize));
+}
+
+/// **Panics** in debug builds if the channel at the given address was destroyed, see [`live_channels`].
+/// Only the address is compared, so the channel is not read.
+pub(crate) fn debug_assert_live(#[allow(unused)] channel: *const u8) {
+    #[cfg(debug_assertions)]
+    assert!(
+        live_channels()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(&(channel as usize)),
+        "the channel was already destroyed"
+    );
+}
+
 /// A directed channel used for communication between threads.
 /// It holds two instances of `Data`, which can be accessed or flushed.
 /// One `Data` can only be read, and the other can only be written to.
@@ -16,8 +104,26 @@ use crate::{ChannelKey, DataKey};
 /// See [DirectedChannel::create] for more info.
 #[derive(Debug)]
 pub struct DirectedChannel<Data> {
-    pub(crate) read_only: Data,
-    pub(crate) writable: Data,
+    pub(crate) read_only: CachePadded<Data>,
+    pub(crate) writable: CachePadded<Data>,
+    flush_count: u64,
+    version: u64,
+    dirty: bool,
+    /// Set by [`DirectedChannelPointer::freeze`] to forbid further flushes.
+    frozen: bool,
+    /// The number of read-only data pointers handed out by [`DirectedChannel::create`] and [`DirectedChannelPointer::new_reader`].
+    reader_count: AtomicUsize,
+    /// Wakes the streams returned by [`DirectedChannelPointer::flush_stream`].
+    #[cfg(feature = "futures")]
+    flush_notifier: Arc<FlushNotifier>,
+    /// The callbacks registered via [`DirectedChannelPointer::on_flush`].
+    observers: Observers<Data>,
+    /// The time of the last flush, see [`DirectedChannelPointer::stats`].
+    #[cfg(feature = "stats")]
+    last_flush: Option<Instant>,
+    /// The number of bytes copied by all flushes, see [`DirectedChannelPointer::stats`].
+    #[cfg(feature = "stats")]
+    bytes_copied: u64,
 }
 
 /// A pointer to a directed channel.
@@ -30,6 +136,16 @@ pub struct DirectedChannelPointer<Data> {
     channel: Box<DirectedChannel<Data>>,
 }
 
+/// A pointer to a directed channel that can no longer be written to, obtained via [`DirectedChannelPointer::into_read_only_mode`].
+/// It can only hand out more readers and be destroyed.
+///
+/// This type should always be destroyed via the [ReadOnlyChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct ReadOnlyChannelPointer<Data> {
+    channel_pointer: DirectedChannelPointer<Data>,
+}
+
 /// A pointer to the read-only data field in a directed channel.
 /// It can only be accessed using a [DataKey].
 ///
@@ -37,7 +153,9 @@ pub struct DirectedChannelPointer<Data> {
 #[derive(Debug)]
 #[must_use]
 pub struct ReadOnlyDataPointer<Data> {
-    pub(crate) data: *const Data,
+    /// Never null, since it always points into a channel.
+    pub(crate) data: NonNull<Data>,
+    pub(crate) version: *const u64,
 }
 
 /// A pointer to the writable data field in a directed channel.
@@ -47,10 +165,419 @@ pub struct ReadOnlyDataPointer<Data> {
 #[derive(Debug)]
 #[must_use]
 pub struct WritableDataPointer<Data> {
-    pub(crate) data: *mut Data,
+    /// Never null, since it always points into a channel.
+    pub(crate) data: NonNull<Data>,
+    pub(crate) dirty: *mut bool,
+}
+
+/// The pointers to a channel created via [`DirectedChannel::create_uninit`].
+pub type CreatedUninit<Data> = (
+    DirectedChannelPointer<MaybeUninit<Data>>,
+    ReadOnlyDataPointer<MaybeUninit<Data>>,
+    WritableDataPointer<MaybeUninit<Data>>,
+);
+
+/// The error returned by [`DirectedChannel::try_destroy`] if not all pointers point to the same channel.
+/// It holds all pointers that were passed to `try_destroy`, such that the caller can retry.
+#[derive(Debug)]
+pub struct DestroyError<Data> {
+    /// Which pointer does not point to the channel.
+    pub kind: DestroyErrorKind,
+    /// The channel pointer passed to `try_destroy`.
+    pub channel_pointer: DirectedChannelPointer<Data>,
+    /// The read-only data pointers passed to `try_destroy`.
+    pub read_only_data_pointers: Vec<ReadOnlyDataPointer<Data>>,
+    /// The writable data pointer passed to `try_destroy`.
+    pub writable_data_pointer: WritableDataPointer<Data>,
+}
+
+/// A copy of the read-only and the writable `Data` of a directed channel,
+/// obtained via [`DirectedChannelPointer::checkpoint`] and restored via [`DirectedChannelPointer::restore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint<Data> {
+    /// The read-only `Data` at the time of the checkpoint.
+    pub read_only: Data,
+    /// The writable `Data` at the time of the checkpoint.
+    pub writable: Data,
+}
+
+/// An owned copy of the read-only `Data` of a directed channel, obtained via [`ReadOnlyDataPointer::owned_snapshot`].
+/// It holds no pointer into the channel, so it can be sent to other threads and outlive the channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedSnapshot<Data> {
+    data: Data,
+    version: u64,
+}
+
+impl<Data> OwnedSnapshot<Data> {
+    /// Get a reference to the copied `Data`.
+    pub fn get(&self) -> &Data {
+        &self.data
+    }
+
+    /// The version of the read-only `Data` at the time of the snapshot, see [`ReadOnlyDataPointer::version`].
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Take the copied `Data` out of the snapshot.
+    pub fn into_inner(self) -> Data {
+        self.data
+    }
+}
+
+impl<Data> Deref for OwnedSnapshot<Data> {
+    type Target = Data;
+
+    fn deref(&self) -> &Data {
+        &self.data
+    }
+}
+
+/// Statistics about the flushes of a directed channel, obtained via [`DirectedChannelPointer::stats`].
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelStats {
+    /// The number of flushes, see [`DirectedChannelPointer::flush_count`].
+    pub flush_count: u64,
+    /// The time of the last flush, or `None` if the channel was not flushed yet.
+    pub last_flush: Option<Instant>,
+    /// The number of bytes copied by all flushes, counting `size_of::<Data>()` per flush.
+    /// This does not include memory owned by `Data`, such as the elements of a `Vec`.
+    pub bytes_copied: u64,
+}
+
+/// A `Data` field of a directed channel.
+/// With the `cache-padded` feature, it is aligned to 64 bytes, such that the read-only and the writable `Data`
+/// never share a cache line, which avoids false sharing between a thread writing one and a thread reading the other.
+/// The tradeoff is that each field then occupies at least 64 bytes, which is significant for small `Data`.
+#[derive(Debug, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cache-padded", repr(align(64)))]
+pub(crate) struct CachePadded<Data>(pub(crate) Data);
+
+impl<Data: Clone> Clone for CachePadded<Data> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.0.clone_from(&source.0);
+    }
+}
+
+impl<Data> Deref for CachePadded<Data> {
+    type Target = Data;
+
+    fn deref(&self) -> &Data {
+        &self.0
+    }
+}
+
+impl<Data> DerefMut for CachePadded<Data> {
+    fn deref_mut(&mut self) -> &mut Data {
+        &mut self.0
+    }
+}
+
+/// A callback registered via [`DirectedChannelPointer::on_flush`].
+pub type Observer<Data> = Box<dyn FnMut(&Data) + Send>;
+
+/// The id of an observer registered via [`DirectedChannelPointer::on_flush`],
+/// used to remove it again via [`DirectedChannelPointer::remove_observer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+/// The observers of a directed channel.
+struct Observers<Data> {
+    observers: Vec<(ObserverId, Observer<Data>)>,
+    next_id: u64,
+}
+
+impl<Data> Observers<Data> {
+    fn new() -> Self {
+        Self {
+            observers: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn notify(&mut self, data: &Data) {
+        for (_, observer) in &mut self.observers {
+            observer(data);
+        }
+    }
+}
+
+impl<Data> Debug for Observers<Data> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_list()
+            .entries(self.observers.iter().map(|(id, _)| id))
+            .finish()
+    }
+}
+
+/// The kind of a [`DestroyError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestroyErrorKind {
+    /// The writable data pointer does not point to the channel.
+    WritableMismatch {
+        /// The address the writable data pointer points to.
+        address: usize,
+    },
+    /// A read-only data pointer does not point to the channel.
+    ReadOnlyMismatch {
+        /// The address the first mismatching read-only data pointer points to.
+        address: usize,
+    },
+    /// Fewer read-only data pointers were returned than were handed out.
+    MissingReaders {
+        /// The number of read-only data pointers that were handed out.
+        expected: usize,
+        /// The number of read-only data pointers that were returned.
+        returned: usize,
+    },
+}
+
+/// Check the pointers passed to [`DirectedChannel::try_destroy`] against the addresses of the `Data` fields of the channel.
+/// The addresses are type-erased, such that the checks are compiled once instead of once per `Data`.
+/// Channels that do not count their readers pass a `reader_count` of zero.
+pub(crate) fn check_destroy(
+    read_only: *const u8,
+    writable: *const u8,
+    reader_count: usize,
+    read_only_data_pointers: &mut dyn Iterator<Item = *const u8>,
+    writable_data_pointer: *const u8,
+) -> Option<DestroyErrorKind> {
+    if writable_data_pointer != writable {
+        return Some(DestroyErrorKind::WritableMismatch {
+            address: writable_data_pointer as usize,
+        });
+    }
+    let mut returned = 0;
+    for read_only_data_pointer in read_only_data_pointers {
+        if read_only_data_pointer != read_only {
+            return Some(DestroyErrorKind::ReadOnlyMismatch {
+                address: read_only_data_pointer as usize,
+            });
+        }
+        returned += 1;
+    }
+    (returned < reader_count).then(|| DestroyErrorKind::MissingReaders {
+        expected: reader_count,
+        returned,
+    })
+}
+
+impl<Data> Display for DestroyError<Data> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl Display for DestroyErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            DestroyErrorKind::WritableMismatch { address } => write!(
+                f,
+                "the writable data pointer to {:#x} does not point to the channel",
+                address
+            ),
+            DestroyErrorKind::ReadOnlyMismatch { address } => write!(
+                f,
+                "the read-only data pointer to {:#x} does not point to the channel",
+                address
+            ),
+            DestroyErrorKind::MissingReaders { expected, returned } => write!(
+                f,
+                "only {} of {} read-only data pointers were returned",
+                returned, expected
+            ),
+        }
+    }
+}
+
+impl<Data: Debug> Error for DestroyError<Data> {}
+
+impl<Data: Default> Default for DirectedChannel<Data> {
+    fn default() -> Self {
+        Self::new(Data::default(), Data::default())
+    }
 }
 
 impl<Data> DirectedChannel<Data> {
+    pub(crate) fn new(read_only: Data, writable: Data) -> Self {
+        Self {
+            read_only: CachePadded(read_only),
+            writable: CachePadded(writable),
+            flush_count: 0,
+            version: 0,
+            dirty: false,
+            frozen: false,
+            reader_count: AtomicUsize::new(0),
+            #[cfg(feature = "futures")]
+            flush_notifier: Default::default(),
+            observers: Observers::new(),
+            #[cfg(feature = "stats")]
+            last_flush: None,
+            #[cfg(feature = "stats")]
+            bytes_copied: 0,
+        }
+    }
+
+    /// Allocate a channel on the heap without a temporary on the stack, and initialise everything but its `Data` fields.
+    /// If `zeroed` is true, the `Data` fields are zeroed, otherwise they are left uninitialised.
+    ///
+    /// The caller has to initialise the `Data` fields before turning the returned pointer into a `Box`,
+    /// unless an all-zero bit pattern resp. uninitialised memory is a valid `Data`.
+    fn allocate(zeroed: bool) -> *mut Self {
+        let layout = Layout::new::<Self>();
+        let channel = unsafe {
+            if zeroed {
+                alloc::alloc_zeroed(layout)
+            } else {
+                alloc::alloc(layout)
+            }
+        } as *mut Self;
+        if channel.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+
+        // The channel was just allocated, so all fields are valid for writes.
+        unsafe {
+            ptr::addr_of_mut!((*channel).flush_count).write(0);
+            ptr::addr_of_mut!((*channel).version).write(0);
+            ptr::addr_of_mut!((*channel).dirty).write(false);
+            ptr::addr_of_mut!((*channel).frozen).write(false);
+            ptr::addr_of_mut!((*channel).reader_count).write(AtomicUsize::new(0));
+            #[cfg(feature = "futures")]
+            ptr::addr_of_mut!((*channel).flush_notifier).write(Default::default());
+            ptr::addr_of_mut!((*channel).observers).write(Observers::new());
+            #[cfg(feature = "stats")]
+            ptr::addr_of_mut!((*channel).last_flush).write(None);
+            #[cfg(feature = "stats")]
+            ptr::addr_of_mut!((*channel).bytes_copied).write(0);
+        }
+        channel
+    }
+
+    /// Reset the bookkeeping of a destroyed channel, such that it can be handed out again via [`DirectedChannel::into_pointers`].
+    /// The `Data` fields are left as they are.
+    pub(crate) fn recycle(&mut self) {
+        self.flush_count = 0;
+        self.version = 0;
+        self.dirty = false;
+        self.frozen = false;
+        self.reader_count = AtomicUsize::new(0);
+        #[cfg(feature = "futures")]
+        {
+            self.flush_notifier = Default::default();
+        }
+        self.observers = Observers::new();
+        #[cfg(feature = "stats")]
+        {
+            self.last_flush = None;
+            self.bytes_copied = 0;
+        }
+    }
+
+    /// Hand out the three pointers to the given channel.
+    pub(crate) fn into_pointers(
+        channel: Box<Self>,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        let mut channel_pointer = DirectedChannelPointer::new(channel);
+        let read_only_data_pointer = channel_pointer.new_reader();
+        let writable_data_pointer = channel_pointer.channel.writable_data_pointer();
+        (
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Called right before the `Data` of the channel is moved out when it is destroyed.
+    /// This ends the streams returned by [`DirectedChannelPointer::flush_stream`].
+    pub(crate) fn close(&mut self) {
+        unregister_live((self as *const Self).cast());
+        #[cfg(feature = "futures")]
+        self.flush_notifier.close();
+    }
+
+    /// Hand out a read-only data pointer and count it, see [`DirectedChannelPointer::new_reader`].
+    pub(crate) fn new_reader(&self) -> ReadOnlyDataPointer<Data> {
+        self.reader_count.fetch_add(1, Ordering::Relaxed);
+        self.read_only_data_pointer()
+    }
+
+    /// See [`DirectedChannelPointer::reader_count`].
+    pub(crate) fn reader_count(&self) -> usize {
+        self.reader_count.load(Ordering::Relaxed)
+    }
+
+    /// Check the given pointers before destroying the channel, see [`check_destroy`].
+    /// The read-only data pointers are consumed up to the first one that does not point to the channel.
+    pub(crate) fn check_destroy(
+        &self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: &WritableDataPointer<Data>,
+    ) -> Option<DestroyErrorKind> {
+        check_destroy(
+            (&self.read_only.0 as *const Data).cast(),
+            (&self.writable.0 as *const Data).cast(),
+            self.reader_count(),
+            &mut read_only_data_pointers
+                .into_iter()
+                .map(|read_only_data_pointer| read_only_data_pointer.data.as_ptr() as *const u8),
+            writable_data_pointer.data.as_ptr() as *const u8,
+        )
+    }
+
+    pub(crate) fn read_only_data_pointer(&self) -> ReadOnlyDataPointer<Data> {
+        ReadOnlyDataPointer {
+            data: NonNull::from(&self.read_only.0),
+            version: (&self.version) as *const u64,
+        }
+    }
+
+    pub(crate) fn writable_data_pointer(&mut self) -> WritableDataPointer<Data> {
+        WritableDataPointer {
+            data: NonNull::from(&mut self.writable.0),
+            dirty: (&mut self.dirty) as *mut bool,
+        }
+    }
+
+    /// **Panics** if the channel was frozen via [`DirectedChannelPointer::freeze`].
+    pub(crate) fn assert_not_frozen(&self) {
+        assert!(!self.frozen, "a frozen channel cannot be flushed");
+    }
+
+    /// Record that a flush was performed.
+    fn flushed(&mut self) {
+        self.flushed_partially();
+        self.dirty = false;
+    }
+
+    /// Record that a flush was performed that copied only parts of the writable `Data`,
+    /// hence the writable `Data` stays dirty.
+    pub(crate) fn flushed_partially(&mut self) {
+        self.flush_count += 1;
+        self.version += 1;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(channel = ?(self as *const Self), version = self.version, "flushed");
+        #[cfg(feature = "futures")]
+        self.flush_notifier.notify(self.version);
+        self.observers.notify(&self.read_only.0);
+        #[cfg(feature = "stats")]
+        {
+            self.last_flush = Some(Instant::now());
+            self.bytes_copied += mem::size_of::<Data>() as u64;
+        }
+    }
+
     /// Create a directed channel and hand out three pointers to it.
     /// One [DirectedChannelPointer] used to flush (copy) the content of the writable `Data` field into the read-only data field,
     /// one [ReadOnlyDataPointer] used to read from the directed channel, and
@@ -67,56 +594,187 @@ impl<Data> DirectedChannel<Data> {
         ReadOnlyDataPointer<Data>,
         WritableDataPointer<Data>,
     ) {
-        let mut channel_pointer = DirectedChannelPointer {
-            channel: Box::new(DirectedChannel {
-                read_only,
-                writable,
-            }),
-        };
-        let read_only_data_pointer = ReadOnlyDataPointer {
-            data: (&channel_pointer.channel.read_only) as *const Data,
-        };
-        let writable_data_pointer = WritableDataPointer {
-            data: (&mut channel_pointer.channel.writable) as *mut Data,
-        };
+        Self::into_pointers(Box::new(DirectedChannel::new(read_only, writable)))
+    }
+
+    /// In this constructor, both `Data` fields are initialised equally by calling `f` twice.
+    /// Unlike [`DirectedChannel::create_equal`], this does not require `Data: Clone`.
+    ///
+    /// See [`DirectedChannel::create`] for more details.
+    pub fn create_equal_with(
+        f: impl Fn() -> Data,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        Self::create(f(), f())
+    }
+
+    /// In this constructor, both `Data` fields are initialised to `Data::default()`.
+    ///
+    /// See [`DirectedChannel::create`] for more details.
+    pub fn create_default() -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    )
+    where
+        Data: Default,
+    {
+        Self::create_equal_with(Data::default)
+    }
+
+    /// Create a directed channel and hand out `readers` [ReadOnlyDataPointer]s to it up front,
+    /// instead of a single one that has to be multiplied via [`DirectedChannelPointer::new_reader`].
+    /// All read-only data pointers point to the same read-only `Data`, and all of them have to be passed to [`DirectedChannel::destroy`].
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn create_with_readers(
+        read_only: Data,
+        writable: Data,
+        readers: usize,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        Vec<ReadOnlyDataPointer<Data>>,
+        WritableDataPointer<Data>,
+    ) {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            Self::create(read_only, writable);
+        channel_pointer
+            .channel
+            .reader_count
+            .store(readers, Ordering::Relaxed);
         (
             channel_pointer,
-            read_only_data_pointer,
+            vec![read_only_data_pointer; readers],
             writable_data_pointer,
         )
     }
 
+    /// Create a directed channel whose `Data` fields are uninitialised, and hand out three pointers to it.
+    /// The channel is allocated directly on the heap, so this is useful for huge `Data` that should not be built on the stack first.
+    ///
+    /// The channel holds `MaybeUninit<Data>` until both fields are initialised,
+    /// e.g. by writing the writable `Data` via [`MaybeUninit::write`] and then flushing it with [`DirectedChannelPointer::flush_copy`]
+    /// or [`DirectedChannelPointer::flush_transform`].
+    /// Afterwards, [`DirectedChannel::assume_init`] turns it into a channel of `Data`.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn create_uninit() -> CreatedUninit<Data> {
+        // Uninitialised memory is a valid `MaybeUninit<Data>`.
+        DirectedChannel::into_pointers(unsafe {
+            Box::from_raw(DirectedChannel::<MaybeUninit<Data>>::allocate(false))
+        })
+    }
+
     /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]).
     /// Compared to [`DirectedChannel::destroy_single`], this function accepts multiple [`ReadOnlyDataPointer`]s for destruction.
     ///
-    /// **Panics** if not all pointers point to the same channel.
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if fewer read-only data pointers are passed than were handed out (see [`DirectedChannelPointer::reader_count`]).
+    ///
+    /// Note that the read-only data pointers are only counted.
+    /// Since they are `Copy`, passing one of them as often as pointers were handed out passes the check,
+    /// even if copies of the others are still in use, and surplus pointers are accepted as well.
     pub fn destroy(
         channel_pointer: DirectedChannelPointer<Data>,
         read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
         writable_data_pointer: WritableDataPointer<Data>,
     ) -> (Data, Data) {
-        let DirectedChannelPointer { mut channel } = channel_pointer;
-        let channel_writable_data_pointer = (&mut channel.writable) as *mut Data;
-        let WritableDataPointer {
-            data: writable_data_pointer,
-        } = writable_data_pointer;
-        assert_eq!(channel_writable_data_pointer, writable_data_pointer);
-        let channel_read_only_data_pointer = (&channel.read_only) as *const Data;
+        Self::try_destroy(
+            channel_pointer,
+            read_only_data_pointers,
+            writable_data_pointer,
+        )
+        .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]).
+    ///
+    /// Unlike [`DirectedChannel::destroy`], this does not panic if not all pointers point to the same channel
+    /// or if read-only data pointers are missing,
+    /// but returns all pointers back in the error, such that the caller can retry with the correct pointers.
+    pub fn try_destroy(
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> Result<(Data, Data), DestroyError<Data>> {
+        let channel = *Self::try_destroy_boxed(
+            channel_pointer,
+            read_only_data_pointers,
+            writable_data_pointer,
+        )?;
+        Ok((channel.read_only.0, channel.writable.0))
+    }
 
-        for read_only_data_pointer in read_only_data_pointers {
-            let ReadOnlyDataPointer {
-                data: read_only_data_pointer,
-            } = read_only_data_pointer;
-            assert_eq!(channel_read_only_data_pointer, read_only_data_pointer);
+    /// Like [`DirectedChannel::try_destroy`], but returns the allocation of the destroyed channel,
+    /// such that it can be reused via [`DirectedChannel::recycle`].
+    pub(crate) fn try_destroy_boxed(
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> Result<Box<Self>, DestroyError<Data>> {
+        channel_pointer.debug_assert_live();
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::trace_span!("destroy", channel = ?(&*channel_pointer.channel as *const Self))
+                .entered();
+        let mut read_only_data_pointers = read_only_data_pointers.into_iter();
+        let mut checked = 0;
+        let mut last_checked = None;
+        let kind = channel_pointer.channel.check_destroy(
+            read_only_data_pointers
+                .by_ref()
+                .inspect(|read_only_data_pointer| {
+                    checked += 1;
+                    last_checked = Some(*read_only_data_pointer);
+                }),
+            &writable_data_pointer,
+        );
+        if let Some(kind) = kind {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?kind, "destroy failed");
+            // The checked pointers point to the channel, except for the last one if it caused the error.
+            let mismatch = match kind {
+                DestroyErrorKind::ReadOnlyMismatch { .. } => {
+                    checked -= 1;
+                    last_checked
+                }
+                _ => None,
+            };
+            let read_only_data_pointers =
+                iter::repeat(channel_pointer.channel.read_only_data_pointer())
+                    .take(checked)
+                    .chain(mismatch)
+                    .chain(read_only_data_pointers)
+                    .collect();
+            return Err(DestroyError {
+                kind,
+                channel_pointer,
+                read_only_data_pointers,
+                writable_data_pointer,
+            });
         }
 
-        (channel.read_only, channel.writable)
+        Ok(Self::destroy_checked(channel_pointer))
+    }
+
+    /// Destroy the channel after its pointers were checked.
+    fn destroy_checked(channel_pointer: DirectedChannelPointer<Data>) -> Box<Self> {
+        let mut channel = channel_pointer.into_channel();
+        channel.close();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(version = channel.version, "destroyed");
+        channel
     }
 
     /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]).
     /// Compared to [`DirectedChannel::destroy`], this function accepts only one [`ReadOnlyDataPointer`] for destruction.
     ///
-    /// **Panics** if not all pointers point to the same channel.
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if more than one read-only data pointer was handed out, e.g. via [`DirectedChannelPointer::new_reader`]
+    /// or [`DirectedChannel::create_with_readers`] (see [`DirectedChannelPointer::reader_count`]).
     pub fn destroy_single(
         channel_pointer: DirectedChannelPointer<Data>,
         read_only_data_pointer: ReadOnlyDataPointer<Data>,
@@ -128,6 +786,173 @@ impl<Data> DirectedChannel<Data> {
             writable_data_pointer,
         )
     }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]).
+    /// Compared to [`DirectedChannel::destroy`], this function accepts the [`ReadOnlyDataPointer`]s as a slice,
+    /// which is convenient since they are `Copy`.
+    ///
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if fewer read-only data pointers are passed than were handed out (see [`DirectedChannelPointer::reader_count`]).
+    pub fn destroy_readers(
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointers: &[ReadOnlyDataPointer<Data>],
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        channel_pointer.debug_assert_live();
+        if let Some(kind) = channel_pointer.channel.check_destroy(
+            read_only_data_pointers.iter().copied(),
+            &writable_data_pointer,
+        ) {
+            panic!("{}", kind);
+        }
+        let channel = *Self::destroy_checked(channel_pointer);
+        (channel.read_only.0, channel.writable.0)
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]),
+    /// where the writable data pointer was downgraded via [`WritableDataPointer::into_read_only`].
+    /// Downgraded pointers can be copied, but one of them has to be passed to prove that the writable data pointer was consumed.
+    ///
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if fewer read-only data pointers are passed than were handed out (see [`DirectedChannelPointer::reader_count`]).
+    pub fn destroy_downgraded(
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        downgraded_writable_data_pointer: ReadOnlyDataPointer<Data>,
+    ) -> (Data, Data) {
+        channel_pointer.debug_assert_live();
+        let channel = &channel_pointer.channel;
+        if let Some(kind) = check_destroy(
+            (&channel.read_only.0 as *const Data).cast(),
+            (&channel.writable.0 as *const Data).cast(),
+            channel.reader_count(),
+            &mut read_only_data_pointers
+                .into_iter()
+                .map(|read_only_data_pointer| read_only_data_pointer.data.as_ptr() as *const u8),
+            downgraded_writable_data_pointer.data.as_ptr() as *const u8,
+        ) {
+            panic!("{}", kind);
+        }
+
+        let channel = *Self::destroy_checked(channel_pointer);
+        (channel.read_only.0, channel.writable.0)
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]),
+    /// without passing back the read-only data pointers.
+    /// Unlike [`DirectedChannel::destroy_unchecked`], this still checks that the writable data pointer points to the channel.
+    ///
+    /// **Panics** if the writable data pointer does not point to the channel.
+    ///
+    /// # Safety
+    ///
+    /// None of the read-only data pointers of the channel may be used after this call,
+    /// i.e. neither the ones handed out on creation nor the ones handed out by [`DirectedChannelPointer::new_reader`].
+    pub unsafe fn destroy_tracked(
+        channel_pointer: DirectedChannelPointer<Data>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        channel_pointer.debug_assert_live();
+        assert!(
+            channel_pointer.owns_writable(&writable_data_pointer),
+            "the writable data pointer does not point to the channel"
+        );
+        let mut channel = channel_pointer.into_channel();
+        channel.close();
+        (channel.read_only.0, channel.writable.0)
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]) without any checks.
+    /// Unlike [`DirectedChannel::destroy`], this neither compares the addresses of the pointers nor counts the read-only data pointers.
+    ///
+    /// # Safety
+    ///
+    /// The writable data pointer must point to the given channel,
+    /// and none of the read-only data pointers of the channel may be used after this call.
+    pub unsafe fn destroy_unchecked(
+        channel_pointer: DirectedChannelPointer<Data>,
+        #[allow(unused)] writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        channel_pointer.debug_assert_live();
+        let mut channel = channel_pointer.into_channel();
+        channel.close();
+        (channel.read_only.0, channel.writable.0)
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]),
+    /// but returns its allocation instead of its `Data`, such that it can be reused via [`DirectedChannel::recreate`].
+    /// The `Data` fields are still contained in the allocation, and are dropped when it is dropped or reused.
+    ///
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if fewer read-only data pointers are passed than were handed out (see [`DirectedChannelPointer::reader_count`]).
+    pub fn destroy_boxed(
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> Box<Self> {
+        Self::try_destroy_boxed(
+            channel_pointer,
+            read_only_data_pointers,
+            writable_data_pointer,
+        )
+        .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a directed channel in the allocation of a channel destroyed via [`DirectedChannel::destroy_boxed`],
+    /// and hand out three pointers to it.
+    /// The previous `Data` fields are dropped and replaced by the given ones, and the channel starts like a new one.
+    /// Any boxed channel can be reused this way, since no pointers to it can be left once it is owned as a `Box`.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn recreate(
+        mut freed: Box<Self>,
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        freed.read_only.0 = read_only;
+        freed.writable.0 = writable;
+        freed.recycle();
+        Self::into_pointers(freed)
+    }
+
+    /// Merge the writable `Data` into the read-only `Data` using the given closure.
+    /// The closure receives the read-only `Data` mutably as first argument, and the writable `Data` as second argument.
+    pub fn flush_with(
+        &mut self,
+        #[allow(unused)] channel_key: &ChannelKey,
+        merge: impl FnOnce(&mut Data, &Data),
+    ) {
+        self.assert_not_frozen();
+        merge(&mut self.read_only, &self.writable);
+        self.flushed();
+    }
+
+    /// Replace the read-only `Data` with the result of applying the given function to the writable `Data`.
+    pub fn flush_transform(
+        &mut self,
+        #[allow(unused)] channel_key: &ChannelKey,
+        f: impl FnOnce(&Data) -> Data,
+    ) {
+        self.assert_not_frozen();
+        let read_only = f(&self.writable);
+        self.read_only.0 = read_only;
+        self.flushed();
+    }
+
+    /// The number of flushes that were performed on this channel.
+    pub fn flush_count(&self) -> u64 {
+        self.flush_count
+    }
+
+    /// The version of the read-only `Data`.
+    /// It starts at zero and is incremented on every flush that writes into the read-only `Data`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
 }
 
 impl<Data: Clone> DirectedChannel<Data> {
@@ -144,20 +969,487 @@ impl<Data: Clone> DirectedChannel<Data> {
         Self::create(data.clone(), data)
     }
 
+    /// Clone the writable `Data` into the read-only `Data`.
+    /// The clone is only assigned once it is complete, so if [`Clone::clone`] panics, the read-only `Data` stays unchanged.
+    /// As a consequence, the read-only `Data` gets a new allocation on every flush, see [`DirectedChannel::flush_in_place`].
     pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        self.assert_not_frozen();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("flush", channel = ?(self as *const Self)).entered();
         self.read_only = self.writable.clone();
+        self.flushed();
+    }
+
+    /// Clone the writable `Data` into the read-only `Data` via [`Clone::clone_from`],
+    /// such that the allocation of the read-only `Data` is reused if possible.
+    ///
+    /// Unlike [`DirectedChannel::flush`], this does not leave the read-only `Data` unchanged if the clone panics,
+    /// e.g. for a `Vec` whose elements may panic when cloned, the read-only `Vec` may be left partially updated.
+    pub fn flush_in_place(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        self.assert_not_frozen();
+        self.read_only.0.clone_from(&self.writable.0);
+        self.flushed();
+    }
+
+    /// Clone the read-only `Data` into the writable `Data`, i.e. the reverse of [`DirectedChannel::flush`].
+    /// This discards all changes to the writable `Data` since the last flush.
+    /// It does not count as a flush, but marks the writable `Data` as clean.
+    pub fn reset(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        self.writable.clone_from(&self.read_only);
+        self.dirty = false;
+    }
+}
+
+impl<Data> DirectedChannel<MaybeUninit<Data>> {
+    /// Turn a channel created via [`DirectedChannel::create_uninit`] into a channel of `Data`, once both its `Data` fields are initialised.
+    /// The `Data` fields are moved into a new allocation without a temporary on the stack,
+    /// and the flush count, the version and the flush streams carry over, while the [flush observers](DirectedChannelPointer::on_flush) are dropped.
+    ///
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if more read-only data pointers were handed out than the one passed (see [`DirectedChannelPointer::reader_count`]).
+    ///
+    /// # Safety
+    ///
+    /// Both the read-only and the writable `Data` of the channel must be initialised.
+    pub unsafe fn assume_init(
+        channel_pointer: DirectedChannelPointer<MaybeUninit<Data>>,
+        read_only_data_pointer: ReadOnlyDataPointer<MaybeUninit<Data>>,
+        writable_data_pointer: WritableDataPointer<MaybeUninit<Data>>,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        channel_pointer.debug_assert_live();
+        if let Some(kind) = channel_pointer
+            .channel
+            .check_destroy([read_only_data_pointer], &writable_data_pointer)
+        {
+            panic!("{}", kind);
+        }
+
+        let mut uninit = channel_pointer.into_channel();
+        uninit.close();
+        let channel = DirectedChannel::<Data>::allocate(false);
+        // The new channel was just allocated, and the `Data` fields of the old one are initialised by the caller.
+        // The old channel holds `MaybeUninit<Data>`, so dropping it does not drop the moved `Data`.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                uninit.read_only.0.as_ptr(),
+                ptr::addr_of_mut!((*channel).read_only.0),
+                1,
+            );
+            ptr::copy_nonoverlapping(
+                uninit.writable.0.as_ptr(),
+                ptr::addr_of_mut!((*channel).writable.0),
+                1,
+            );
+            (*channel).flush_count = uninit.flush_count;
+            (*channel).version = uninit.version;
+            (*channel).dirty = uninit.dirty;
+            (*channel).frozen = uninit.frozen;
+            (*channel).reader_count = AtomicUsize::new(uninit.reader_count.load(Ordering::Relaxed));
+            #[cfg(feature = "futures")]
+            {
+                (*channel).flush_notifier = uninit.flush_notifier.clone();
+            }
+            #[cfg(feature = "stats")]
+            {
+                (*channel).last_flush = uninit.last_flush;
+                (*channel).bytes_copied = uninit.bytes_copied;
+            }
+        }
+        let mut channel = unsafe { Box::from_raw(channel) };
+        let read_only_data_pointer = channel.read_only_data_pointer();
+        let writable_data_pointer = channel.writable_data_pointer();
+        (
+            DirectedChannelPointer::new(channel),
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+}
+
+impl<Data> DirectedChannelPointer<MaybeUninit<Data>> {
+    /// Shorthand for [DirectedChannel::assume_init].
+    ///
+    /// # Safety
+    ///
+    /// See [DirectedChannel::assume_init].
+    pub unsafe fn assume_init(
+        self,
+        read_only_data_pointer: ReadOnlyDataPointer<MaybeUninit<Data>>,
+        writable_data_pointer: WritableDataPointer<MaybeUninit<Data>>,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        DirectedChannel::assume_init(self, read_only_data_pointer, writable_data_pointer)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<Data: bytemuck::Zeroable> DirectedChannel<Data> {
+    /// Create a directed channel whose `Data` fields are zeroed, and hand out three pointers to it.
+    /// Like [`DirectedChannel::create_uninit`], the channel is allocated directly on the heap,
+    /// but since `Data` is [`Zeroable`](bytemuck::Zeroable), the channel is initialised right away.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn create_zeroed() -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        // An all-zero bit pattern is a valid `Data`.
+        Self::into_pointers(unsafe { Box::from_raw(Self::allocate(true)) })
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<Data: bytemuck::Pod> DirectedChannel<Data> {
+    /// Copy the bytes of the writable `Data` into the read-only `Data`.
+    /// Like [`DirectedChannel::flush_copy`], this does not run any clone logic,
+    /// but it makes explicit that `Data` is plain old data, e.g. a `#[repr(C)]` struct shared via FFI.
+    pub fn flush_pod(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        self.assert_not_frozen();
+        bytemuck::bytes_of_mut(&mut self.read_only.0)
+            .copy_from_slice(bytemuck::bytes_of(&self.writable.0));
+        self.flushed();
+    }
+}
+
+impl<Data: Copy> DirectedChannel<Data> {
+    /// Copy the writable `Data` into the read-only `Data` bitwise.
+    /// This is only available for `Copy` types, and avoids the overhead of calling [`Clone::clone`].
+    pub fn flush_copy(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        self.assert_not_frozen();
+        self.read_only = self.writable;
+        self.flushed();
     }
 }
 
 impl<Data: Clone> DirectedChannelPointer<Data> {
     /// Clone the writable `Data` into the read-only `Data`.
-    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+    ///
+    /// See [`DirectedChannel::flush`].
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        self.channel.flush(channel_key);
+    }
+
+    /// Clone the writable `Data` into the read-only `Data`, reusing the allocation of the read-only `Data`.
+    ///
+    /// See [`DirectedChannel::flush_in_place`].
+    pub fn flush_in_place(&mut self, channel_key: &ChannelKey) {
+        self.channel.flush_in_place(channel_key);
+    }
+
+    /// Clone the read-only `Data` into the writable `Data`.
+    ///
+    /// See [`DirectedChannel::reset`].
+    pub fn reset(&mut self, channel_key: &ChannelKey) {
+        self.channel.reset(channel_key);
+    }
+
+    /// Clone the read-only and the writable `Data` of this channel into a checkpoint.
+    pub fn checkpoint(&self, #[allow(unused)] data_key: &DataKey) -> Checkpoint<Data> {
+        Checkpoint {
+            read_only: self.channel.read_only.0.clone(),
+            writable: self.channel.writable.0.clone(),
+        }
+    }
+
+    /// Create a new directed channel with clones of the read-only and the writable `Data` of this channel,
+    /// e.g. to fork the state for speculative execution.
+    /// The new channel is independent of this one, and has its own pointers.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn deep_clone(
+        &self,
+        #[allow(unused)] data_key: &DataKey,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        DirectedChannel::create(
+            self.channel.read_only.0.clone(),
+            self.channel.writable.0.clone(),
+        )
+    }
+}
+
+impl<Data: Copy> DirectedChannelPointer<Data> {
+    /// Copy the writable `Data` into the read-only `Data` bitwise.
+    ///
+    /// See [`DirectedChannel::flush_copy`].
+    pub fn flush_copy(&mut self, channel_key: &ChannelKey) {
+        self.channel.flush_copy(channel_key);
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<Data: bytemuck::Pod> DirectedChannelPointer<Data> {
+    /// Copy the bytes of the writable `Data` into the read-only `Data`.
+    ///
+    /// See [`DirectedChannel::flush_pod`].
+    pub fn flush_pod(&mut self, channel_key: &ChannelKey) {
+        self.channel.flush_pod(channel_key);
+    }
+}
+
+impl<Data: Clone> DirectedChannelPointer<Data> {
+    /// Clone the writable `Data` into the read-only `Data`, but only if the writable `Data` was accessed mutably since the last flush.
+    /// Returns `true` if the `Data` was cloned.
+    ///
+    /// The writable `Data` is marked as dirty by [`WritableDataPointer::get_mut`], and every flush marks it as clean again.
+    pub fn flush_dirty(&mut self, channel_key: &ChannelKey) -> bool {
+        if self.channel.dirty {
+            self.channel.flush(channel_key);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<Data: Clone> DirectedChannelPointer<Data> {
+    /// Clone the writable `Data` into the read-only `Data`, but only if the version of the read-only `Data` is the expected one,
+    /// i.e. if nobody else flushed this channel since the caller observed that version.
+    /// Returns the new version if the `Data` was cloned, and the current version otherwise.
+    ///
+    /// See [`ReadOnlyDataPointer::version`].
+    pub fn flush_if_version(
+        &mut self,
+        channel_key: &ChannelKey,
+        expected: u64,
+    ) -> Result<u64, u64> {
+        if self.channel.version == expected {
+            self.channel.flush(channel_key);
+            Ok(self.channel.version)
+        } else {
+            Err(self.channel.version)
+        }
+    }
+}
+
+impl<Data: Clone + PartialEq> DirectedChannelPointer<Data> {
+    /// Clone the writable `Data` into the read-only `Data`, but only if they differ.
+    /// Returns `true` if the `Data` was cloned, and `false` if both `Data`s were already equal.
+    /// Only the former counts towards the [flush count](DirectedChannelPointer::flush_count).
+    pub fn flush_if_changed(&mut self, #[allow(unused)] channel_key: &ChannelKey) -> bool {
+        let channel: &mut DirectedChannel<Data> = &mut self.channel;
+        channel.assert_not_frozen();
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::trace_span!("flush_if_changed", channel = ?(channel as *const DirectedChannel<Data>))
+                .entered();
+        if channel.read_only != channel.writable {
+            channel.read_only = channel.writable.clone();
+            channel.flushed();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<Data> DirectedChannelPointer<Data> {
+    /// Write the read-only and the writable `Data` of the given checkpoint back into this channel.
+    /// This counts as a flush, since the read-only `Data` changes.
+    pub fn restore(
+        &mut self,
+        checkpoint: Checkpoint<Data>,
+        #[allow(unused)] channel_key: &ChannelKey,
+    ) {
         let channel: &mut DirectedChannel<Data> = &mut self.channel;
-        channel.read_only = channel.writable.clone();
+        channel.assert_not_frozen();
+        let Checkpoint {
+            read_only,
+            writable,
+        } = checkpoint;
+        channel.writable.0 = writable;
+        channel.read_only.0 = read_only;
+        channel.flushed();
     }
 }
 
 impl<Data> DirectedChannelPointer<Data> {
+    /// Swap the writable `Data` with the read-only `Data`.
+    ///
+    /// Unlike [`DirectedChannelPointer::flush`], this is a swap and not a copy, so it does not require `Data: Clone`.
+    /// After the swap, the read-only `Data` holds the previously writable value,
+    /// and the writable `Data` holds the previously read-only value, which the writer can reuse or overwrite.
+    pub fn flush_swap(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut DirectedChannel<Data> = &mut self.channel;
+        channel.assert_not_frozen();
+        mem::swap(&mut channel.read_only, &mut channel.writable);
+        channel.flushed();
+    }
+
+    /// Merge the writable `Data` into the read-only `Data` using the given closure.
+    /// The closure receives the read-only `Data` mutably as first argument, and the writable `Data` as second argument.
+    ///
+    /// See [`DirectedChannel::flush_with`].
+    pub fn flush_with(&mut self, channel_key: &ChannelKey, merge: impl FnOnce(&mut Data, &Data)) {
+        self.channel.flush_with(channel_key, merge);
+    }
+
+    /// Replace the read-only `Data` with the result of applying the given function to the writable `Data`.
+    ///
+    /// See [`DirectedChannel::flush_transform`].
+    pub fn flush_transform(&mut self, channel_key: &ChannelKey, f: impl FnOnce(&Data) -> Data) {
+        self.channel.flush_transform(channel_key, f);
+    }
+
+    #[cfg(feature = "futures")]
+    pub(crate) fn flush_notifier(&self) -> &Arc<FlushNotifier> {
+        &self.channel.flush_notifier
+    }
+
+    /// The read-only `Data` of this channel during the flush phase.
+    /// Since this borrows the pointer, the channel cannot be flushed at the same time,
+    /// and the readers do not write to it.
+    pub(crate) fn read_only(&self, #[allow(unused)] channel_key: &ChannelKey) -> &Data {
+        &self.channel.read_only.0
+    }
+
+    /// Wrap a newly handed out channel, see [`register_live`].
+    fn new(channel: Box<DirectedChannel<Data>>) -> Self {
+        register_live((&*channel as *const DirectedChannel<Data>).cast());
+        Self { channel }
+    }
+
+    /// **Panics** in debug builds if the channel was already destroyed, see [`debug_assert_live`].
+    fn debug_assert_live(&self) {
+        debug_assert_live(ptr::addr_of!(*self.channel).cast());
+    }
+
+    /// Take the channel out of this pointer without running the leak check.
+    fn into_channel(self) -> Box<DirectedChannel<Data>> {
+        let channel_pointer = mem::ManuallyDrop::new(self);
+        unsafe { ptr::read(&channel_pointer.channel) }
+    }
+
+    /// Create a new [`ReadOnlyDataPointer`] to the read-only `Data` field of this channel.
+    /// It has to be passed to [`DirectedChannel::destroy`] like the one returned from [`DirectedChannel::create`].
+    pub fn new_reader(&self) -> ReadOnlyDataPointer<Data> {
+        self.debug_assert_live();
+        self.channel.new_reader()
+    }
+
+    /// The number of read-only data pointers handed out by [`DirectedChannel::create`] and [`DirectedChannelPointer::new_reader`].
+    /// At least this many read-only data pointers have to be passed to [`DirectedChannel::destroy`].
+    ///
+    /// This is only a count: the destroy functions do not check that each handed out pointer was passed back,
+    /// so it does not prove that no copy of a read-only data pointer is still in use.
+    pub fn reader_count(&self) -> usize {
+        self.channel.reader_count()
+    }
+
+    /// Convert this pointer into a raw pointer to the channel, e.g. to store it in an opaque handle across an FFI boundary.
+    /// The channel is leaked until it is reclaimed via [`DirectedChannelPointer::from_raw`].
+    pub fn into_raw(self) -> *mut DirectedChannel<Data> {
+        Box::into_raw(self.into_channel())
+    }
+
+    /// Reclaim a channel pointer from a raw pointer returned by [`DirectedChannelPointer::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// The raw pointer must have been returned by [`DirectedChannelPointer::into_raw`],
+    /// and must not be reclaimed more than once.
+    ///
+    /// **Panics** in debug builds if the channel was already destroyed.
+    pub unsafe fn from_raw(channel: *mut DirectedChannel<Data>) -> Self {
+        debug_assert_live(channel as *const u8);
+        Self {
+            channel: Box::from_raw(channel),
+        }
+    }
+
+    /// Returns `true` if the given pointer points to the read-only `Data` field of this channel.
+    pub fn owns_read_only(&self, read_only_data_pointer: &ReadOnlyDataPointer<Data>) -> bool {
+        ptr::eq(
+            &self.channel.read_only.0,
+            read_only_data_pointer.data.as_ptr(),
+        )
+    }
+
+    /// Returns `true` if the given pointer points to the writable `Data` field of this channel.
+    pub fn owns_writable(&self, writable_data_pointer: &WritableDataPointer<Data>) -> bool {
+        ptr::eq(
+            &self.channel.writable.0,
+            writable_data_pointer.data.as_ptr(),
+        )
+    }
+
+    /// The number of flushes that were performed via this pointer.
+    ///
+    /// See [`DirectedChannel::flush_count`].
+    pub fn flush_count(&self) -> u64 {
+        self.channel.flush_count()
+    }
+
+    /// Convert this channel such that its read-only `Data` can never change again, e.g. after the writer finished.
+    /// This consumes the writable data pointer, so the returned pointer statically prevents any further flushes and writes.
+    ///
+    /// **Panics** if the writable data pointer does not point to this channel.
+    pub fn into_read_only_mode(
+        self,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> ReadOnlyChannelPointer<Data> {
+        assert!(
+            self.owns_writable(&writable_data_pointer),
+            "the writable data pointer must point to this channel"
+        );
+        ReadOnlyChannelPointer {
+            channel_pointer: self,
+        }
+    }
+
+    /// Statistics about the flushes of this channel, e.g. to find out which channels are hot.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> ChannelStats {
+        ChannelStats {
+            flush_count: self.channel.flush_count,
+            last_flush: self.channel.last_flush,
+            bytes_copied: self.channel.bytes_copied,
+        }
+    }
+
+    /// Register a callback that is invoked with the new read-only `Data` whenever this channel is flushed.
+    /// It runs inside the flush, i.e. on the flushing thread under the [ChannelKey], after the read-only `Data` was updated.
+    /// Returns the id of the observer, which can be used to remove it again via [`DirectedChannelPointer::remove_observer`].
+    pub fn on_flush(&mut self, observer: Observer<Data>) -> ObserverId {
+        let observers = &mut self.channel.observers;
+        let id = ObserverId(observers.next_id);
+        observers.next_id += 1;
+        observers.observers.push((id, observer));
+        id
+    }
+
+    /// Remove the observer with the given id, such that it is no longer invoked on flushes.
+    /// Returns the removed observer, or `None` if there is no observer with this id.
+    pub fn remove_observer(&mut self, id: ObserverId) -> Option<Observer<Data>> {
+        let observers = &mut self.channel.observers.observers;
+        let index = observers
+            .iter()
+            .position(|(observer_id, _)| *observer_id == id)?;
+        Some(observers.remove(index).1)
+    }
+
+    /// Forbid all further flushes of this channel, e.g. to protect a final configuration against accidental late writes.
+    /// Afterwards, all flush operations panic, but the read-only `Data` can still be read.
+    pub fn freeze(&mut self) {
+        self.channel.frozen = true;
+    }
+
+    /// Returns `true` if this channel was frozen via [`DirectedChannelPointer::freeze`].
+    pub fn is_frozen(&self) -> bool {
+        self.channel.frozen
+    }
+
     /// Shorthand for [DirectedChannel::destroy].
     pub fn destroy(
         self,
@@ -175,62 +1467,693 @@ impl<Data> DirectedChannelPointer<Data> {
     ) -> (Data, Data) {
         DirectedChannel::destroy_single(self, read_only_data_pointer, writable_data_pointer)
     }
+
+    /// Shorthand for [DirectedChannel::destroy_readers].
+    pub fn destroy_readers(
+        self,
+        read_only_data_pointers: &[ReadOnlyDataPointer<Data>],
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        DirectedChannel::destroy_readers(self, read_only_data_pointers, writable_data_pointer)
+    }
+
+    /// Shorthand for [DirectedChannel::destroy_downgraded].
+    pub fn destroy_downgraded(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        downgraded_writable_data_pointer: ReadOnlyDataPointer<Data>,
+    ) -> (Data, Data) {
+        DirectedChannel::destroy_downgraded(
+            self,
+            read_only_data_pointers,
+            downgraded_writable_data_pointer,
+        )
+    }
+
+    /// Shorthand for [DirectedChannel::destroy_boxed].
+    pub fn destroy_boxed(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> Box<DirectedChannel<Data>> {
+        DirectedChannel::destroy_boxed(self, read_only_data_pointers, writable_data_pointer)
+    }
+
+    /// Shorthand for [DirectedChannel::destroy_tracked].
+    ///
+    /// # Safety
+    ///
+    /// See [DirectedChannel::destroy_tracked].
+    pub unsafe fn destroy_tracked(
+        self,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        DirectedChannel::destroy_tracked(self, writable_data_pointer)
+    }
+}
+
+impl<Data> ReadOnlyChannelPointer<Data> {
+    /// Create a new [`ReadOnlyDataPointer`] to the read-only `Data` field of this channel.
+    ///
+    /// See [`DirectedChannelPointer::new_reader`].
+    pub fn new_reader(&self) -> ReadOnlyDataPointer<Data> {
+        self.channel_pointer.new_reader()
+    }
+
+    /// The number of read-only data pointers handed out for this channel.
+    ///
+    /// See [`DirectedChannelPointer::reader_count`].
+    pub fn reader_count(&self) -> usize {
+        self.channel_pointer.reader_count()
+    }
+
+    /// Destroys the directed channel linked with the given pointers.
+    /// Returns the read-only and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to this channel,
+    /// or if fewer read-only data pointers are passed than were handed out.
+    pub fn destroy(
+        mut self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+    ) -> (Data, Data) {
+        let writable_data_pointer = self.channel_pointer.channel.writable_data_pointer();
+        self.channel_pointer
+            .destroy(read_only_data_pointers, writable_data_pointer)
+    }
 }
 
 impl<Data> ReadOnlyDataPointer<Data> {
     /// Get a reference to the `Data` field pointed to by this pointer.
     pub fn get(&self, #[allow(unused)] data_key: &DataKey) -> &Data {
-        unsafe { &*self.data }
+        unsafe { self.data.as_ref() }
+    }
+
+    /// Get the version of the `Data` field pointed to by this pointer.
+    /// The version is incremented on every flush, so a reader can cache it to detect whether new `Data` was published.
+    ///
+    /// See [`DirectedChannel::version`].
+    pub fn version(&self, #[allow(unused)] data_key: &DataKey) -> u64 {
+        unsafe { *self.version }
+    }
+
+    /// Get the raw pointer to the `Data` field pointed to by this pointer, e.g. to pass it across an FFI boundary.
+    ///
+    /// **Warning:** Accessing the `Data` via the raw pointer bypasses the key-based protection of this crate.
+    /// The caller is responsible for never reading the `Data` while the channel is flushed.
+    pub fn as_ptr(&self) -> *const Data {
+        self.data.as_ptr()
+    }
+
+    /// Project this pointer onto a part of the `Data` field pointed to by this pointer, e.g. a struct field.
+    /// The given function is called once to compute the address of the part, which is then stored in the resulting pointer.
+    /// The resulting pointer has the same version as this pointer.
+    ///
+    /// **Panics** if the returned reference does not point into the memory of `Data` itself,
+    /// e.g. if it points into a heap allocation owned by `Data`, since that allocation may be replaced by a flush.
+    ///
+    /// # Safety
+    ///
+    /// The part must be a valid `U` at the same address for every value of `Data`, i.e. a field of a struct, a tuple or an array.
+    /// For example, the payload of an enum variant is not, since a flush may publish a different variant.
+    ///
+    /// Projected pointers are not counted by [`DirectedChannelPointer::reader_count`] and cannot be passed to [`DirectedChannel::destroy`],
+    /// so they must not be used after the channel was destroyed.
+    pub unsafe fn project<U>(
+        self,
+        data_key: &DataKey,
+        f: impl FnOnce(&Data) -> &U,
+    ) -> ReadOnlyDataPointer<U> {
+        let data = self.data.as_ptr() as usize;
+        let projected = NonNull::from(f(self.get(data_key)));
+        assert!(
+            data <= projected.as_ptr() as usize
+                && projected.as_ptr() as usize + mem::size_of::<U>()
+                    <= data + mem::size_of::<Data>(),
+            "a projection must point into the memory of the projected data"
+        );
+
+        ReadOnlyDataPointer {
+            data: projected,
+            version: self.version,
+        }
+    }
+}
+
+impl<Data: Clone> ReadOnlyDataPointer<Data> {
+    /// Get an owned clone of the `Data` field pointed to by this pointer.
+    /// The clone is independent of the channel, so it is not affected by later flushes.
+    pub fn snapshot(&self, data_key: &DataKey) -> Data {
+        self.get(data_key).clone()
+    }
+}
+
+impl<Data: Clone + Send> ReadOnlyDataPointer<Data> {
+    /// Get an owned clone of the `Data` field pointed to by this pointer, together with its version.
+    /// Like [`ReadOnlyDataPointer::snapshot`], but the returned [`OwnedSnapshot`] documents in its type
+    /// that it is decoupled from the channel, e.g. to send it to another thread for processing.
+    pub fn owned_snapshot(&self, data_key: &DataKey) -> OwnedSnapshot<Data> {
+        OwnedSnapshot {
+            data: self.snapshot(data_key),
+            version: self.version(data_key),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Data: serde::Serialize> ReadOnlyDataPointer<Data> {
+    /// Serialize the `Data` field pointed to by this pointer with the given serializer.
+    /// Since this requires a [DataKey], the serialization cannot race with a flush.
+    pub fn serialize_value<S: serde::Serializer>(
+        &self,
+        data_key: &DataKey,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.get(data_key).serialize(serializer)
+    }
+}
+
+impl<Data: Debug> ReadOnlyDataPointer<Data> {
+    /// Get a value that formats the `Data` field pointed to by this pointer via [`Debug`].
+    /// Unlike the `Debug` implementation of this pointer, which only shows the addresses, this shows the actual `Data`.
+    pub fn debug_value<'a>(&'a self, data_key: &'a DataKey) -> impl Debug + 'a {
+        self.get(data_key)
+    }
+}
+
+impl<Data: Copy> ReadOnlyDataPointer<Data> {
+    /// Get a copy of the `Data` field pointed to by this pointer.
+    pub fn read_copy(&self, data_key: &DataKey) -> Data {
+        *self.get(data_key)
     }
 }
 
 impl<Data> WritableDataPointer<Data> {
     /// Get a reference to the `Data` field pointed to by this pointer.
     pub fn get(&self, #[allow(unused)] data_key: &DataKey) -> &Data {
-        unsafe { &*self.data }
+        unsafe { self.data.as_ref() }
     }
 
     /// Get a mutable reference to the `Data` field pointed to by this pointer.
+    /// This marks the `Data` as dirty, see [`DirectedChannelPointer::flush_dirty`].
     pub fn get_mut(&mut self, #[allow(unused)] data_key: &DataKey) -> &mut Data {
-        unsafe { &mut *self.data }
+        unsafe {
+            *self.dirty = true;
+            self.data.as_mut()
+        }
     }
-}
 
-impl<Data> Clone for ReadOnlyDataPointer<Data> {
-    fn clone(&self) -> Self {
-        *self
+    /// Get mutable references to two disjoint parts of the `Data` field pointed to by this pointer,
+    /// e.g. to two different fields of a struct.
+    /// The parts are selected by the given functions, which map the raw pointer to the `Data` to raw pointers to the parts,
+    /// e.g. via [`ptr::addr_of_mut`].
+    /// This marks the `Data` as dirty, see [`DirectedChannelPointer::flush_dirty`].
+    ///
+    /// **Panics** if a returned pointer does not point into the memory of `Data` itself,
+    /// or if the two returned pointers overlap.
+    ///
+    /// # Safety
+    ///
+    /// The functions must only compute the pointers to the parts, without reading or writing the `Data` through the given pointer.
+    /// The returned pointers must point to valid, properly aligned values of `A` and `B` that are stored inline in the `Data`,
+    /// i.e. to fields of the `Data` and not, for example, into the payload of an enum variant or behind an indirection.
+    pub unsafe fn get_disjoint_mut<A, B>(
+        &mut self,
+        #[allow(unused)] data_key: &DataKey,
+        fa: impl FnOnce(*mut Data) -> *mut A,
+        fb: impl FnOnce(*mut Data) -> *mut B,
+    ) -> (&mut A, &mut B) {
+        *self.dirty = true;
+        let data = self.data.as_ptr();
+        let a = fa(data);
+        let b = fb(data);
+
+        let data_range = data as usize..data as usize + mem::size_of::<Data>();
+        let a_range = a as usize..a as usize + mem::size_of::<A>();
+        let b_range = b as usize..b as usize + mem::size_of::<B>();
+        assert!(
+            data_range.start <= a_range.start
+                && a_range.end <= data_range.end
+                && data_range.start <= b_range.start
+                && b_range.end <= data_range.end,
+            "a disjoint reference must point into the memory of the data"
+        );
+        assert!(
+            a_range.end <= b_range.start || b_range.end <= a_range.start,
+            "disjoint references must not overlap"
+        );
+
+        unsafe { (&mut *a, &mut *b) }
     }
-}
 
-impl<Data> Copy for ReadOnlyDataPointer<Data> {}
+    /// Get the raw pointer to the `Data` field pointed to by this pointer, e.g. to pass it across an FFI boundary.
+    ///
+    /// **Warning:** Accessing the `Data` via the raw pointer bypasses the key-based protection of this crate.
+    /// The caller is responsible for never accessing the `Data` while the channel is flushed.
+    /// Also, writes via the raw pointer do not mark the `Data` as dirty.
+    pub fn as_mut_ptr(&mut self) -> *mut Data {
+        self.data.as_ptr()
+    }
 
-unsafe impl<Data> Send for DirectedChannelPointer<Data> {}
-unsafe impl<Data> Send for ReadOnlyDataPointer<Data> {}
-unsafe impl<Data> Send for WritableDataPointer<Data> {}
+    /// Replace the `Data` field pointed to by this pointer with the given value.
+    pub fn set(&mut self, data_key: &DataKey, value: Data) {
+        *self.get_mut(data_key) = value;
+    }
 
-unsafe impl<Data> Sync for DirectedChannelPointer<Data> {}
-unsafe impl<Data> Sync for ReadOnlyDataPointer<Data> {}
-unsafe impl<Data> Sync for WritableDataPointer<Data> {}
+    /// Modify the `Data` field pointed to by this pointer in place using the given function.
+    pub fn modify(&mut self, data_key: &DataKey, f: impl FnOnce(&mut Data)) {
+        f(self.get_mut(data_key));
+    }
 
-/// Object-safe trait for [`DirectedChannelPointer`]s.
-pub trait IDirectedChannel: Send + Sync {
-    /// Perform the [`DirectedChannelPointer::flush`] operation.
-    fn flush(&mut self, channel_key: &ChannelKey);
+    /// Convert this pointer into a read-only pointer.
+    ///
+    /// Note that the resulting pointer still points to the **writable** `Data` field,
+    /// and not to the read-only `Data` field pointed to by the [`ReadOnlyDataPointer`] returned from [`DirectedChannel::create`].
+    /// So it observes the writable `Data` directly instead of the flushed `Data`.
+    /// Its [version](ReadOnlyDataPointer::version) is always zero, since the writable `Data` is not versioned.
+    ///
+    /// A downgraded pointer must be destroyed via [`DirectedChannel::destroy_downgraded`].
+    pub fn into_read_only(self) -> ReadOnlyDataPointer<Data> {
+        ReadOnlyDataPointer {
+            data: self.data,
+            version: (&UNVERSIONED) as *const u64,
+        }
+    }
 }
 
-impl<Data: Clone> IDirectedChannel for DirectedChannelPointer<Data> {
-    fn flush(&mut self, channel_key: &ChannelKey) {
-        DirectedChannelPointer::flush(self, channel_key);
+impl<Data: Debug> WritableDataPointer<Data> {
+    /// Get a value that formats the `Data` field pointed to by this pointer via [`Debug`].
+    /// Unlike the `Debug` implementation of this pointer, which only shows the addresses, this shows the actual `Data`.
+    pub fn debug_value<'a>(&'a self, data_key: &'a DataKey) -> impl Debug + 'a {
+        self.get(data_key)
     }
 }
 
-#[cfg(test)]
+impl<Data: Copy> WritableDataPointer<Data> {
+    /// Get a copy of the `Data` field pointed to by this pointer.
+    pub fn read_copy(&self, data_key: &DataKey) -> Data {
+        *self.get(data_key)
+    }
+}
+
+/// With the `leak-check` feature, dropping a channel pointer instead of destroying it logs a warning,
+/// and with the `leak-check-abort` feature, it aborts the process (unless the thread is already panicking).
+#[cfg(feature = "leak-check")]
+impl<Data> Drop for DirectedChannelPointer<Data> {
+    fn drop(&mut self) {
+        log::warn!(
+            "the directed channel at {:p} was dropped without being destroyed",
+            &*self.channel
+        );
+        #[cfg(feature = "leak-check-abort")]
+        if !std::thread::panicking() {
+            std::process::abort();
+        }
+    }
+}
+
+impl<Data> Clone for ReadOnlyDataPointer<Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Data> Copy for ReadOnlyDataPointer<Data> {}
+
+/// Pointers are compared by identity, i.e. two pointers are equal if they point to the same channel.
+impl<Data> PartialEq for DirectedChannelPointer<Data> {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(&*self.channel, &*other.channel)
+    }
+}
+
+impl<Data> Eq for DirectedChannelPointer<Data> {}
+
+impl<Data> Hash for DirectedChannelPointer<Data> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(&*self.channel, state);
+    }
+}
+
+/// Pointers are compared by identity, i.e. two pointers are equal if they point to the same `Data` field.
+impl<Data> PartialEq for ReadOnlyDataPointer<Data> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<Data> Eq for ReadOnlyDataPointer<Data> {}
+
+impl<Data> Hash for ReadOnlyDataPointer<Data> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.data.as_ptr(), state);
+    }
+}
+
+/// Pointers are compared by identity, i.e. two pointers are equal if they point to the same `Data` field.
+impl<Data> PartialEq for WritableDataPointer<Data> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<Data> Eq for WritableDataPointer<Data> {}
+
+impl<Data> Hash for WritableDataPointer<Data> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.data.as_ptr(), state);
+    }
+}
+
+/// A pointer to the read-only data field in a directed channel that borrows the channel pointer.
+/// It can only be accessed using a [DataKey].
+///
+/// Unlike a [`ReadOnlyDataPointer`], the borrow checker ensures that this pointer does not outlive its channel,
+/// so it does not need to be destroyed.
+/// See [`DirectedChannelPointer::borrow_pointers`] for more info.
+#[derive(Debug)]
+pub struct BorrowedReadOnlyDataPointer<'channel, Data> {
+    pointer: ReadOnlyDataPointer<Data>,
+    scope: PhantomData<&'channel ()>,
+}
+
+/// A pointer to the writable data field in a directed channel that borrows the channel pointer and its writable data pointer.
+/// It can only be accessed using a [DataKey].
+///
+/// Since it borrows the [`WritableDataPointer`] mutably, the writable `Data` cannot be accessed through both at the same time.
+/// See [`DirectedChannelPointer::borrow_pointers`] for more info.
+#[derive(Debug)]
+pub struct BorrowedWritableDataPointer<'channel, Data> {
+    pointer: &'channel mut WritableDataPointer<Data>,
+}
+
+impl<Data> DirectedChannelPointer<Data> {
+    /// Create a read-only data pointer that mutably borrows this channel pointer,
+    /// and a writable data pointer that additionally borrows the given writable data pointer of this channel mutably.
+    /// Since they borrow the channel pointer, the channel can neither be flushed nor destroyed while they exist.
+    ///
+    /// This is useful for scoped threads, e.g. via [`std::thread::scope`]:
+    /// the threads in the scope access the data via the borrowed pointers, and after the scope ends, the channel is flushed.
+    ///
+    /// **Panics** if the writable data pointer does not point to this channel.
+    pub fn borrow_pointers<'channel>(
+        &'channel mut self,
+        writable_data_pointer: &'channel mut WritableDataPointer<Data>,
+    ) -> (
+        BorrowedReadOnlyDataPointer<'channel, Data>,
+        BorrowedWritableDataPointer<'channel, Data>,
+    ) {
+        assert!(self.owns_writable(writable_data_pointer));
+        (
+            BorrowedReadOnlyDataPointer {
+                pointer: self.channel.read_only_data_pointer(),
+                scope: PhantomData,
+            },
+            BorrowedWritableDataPointer {
+                pointer: writable_data_pointer,
+            },
+        )
+    }
+}
+
+impl<'channel, Data> BorrowedReadOnlyDataPointer<'channel, Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer.
+    pub fn get(&self, data_key: &DataKey) -> &Data {
+        self.pointer.get(data_key)
+    }
+
+    /// Get the version of the `Data` field pointed to by this pointer.
+    ///
+    /// See [`ReadOnlyDataPointer::version`].
+    pub fn version(&self, data_key: &DataKey) -> u64 {
+        self.pointer.version(data_key)
+    }
+}
+
+impl<'channel, Data> BorrowedWritableDataPointer<'channel, Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer.
+    pub fn get(&self, data_key: &DataKey) -> &Data {
+        self.pointer.get(data_key)
+    }
+
+    /// Get a mutable reference to the `Data` field pointed to by this pointer.
+    ///
+    /// See [`WritableDataPointer::get_mut`].
+    pub fn get_mut(&mut self, data_key: &DataKey) -> &mut Data {
+        self.pointer.get_mut(data_key)
+    }
+}
+
+impl<'channel, Data> Clone for BorrowedReadOnlyDataPointer<'channel, Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'channel, Data> Copy for BorrowedReadOnlyDataPointer<'channel, Data> {}
+
+// The pointers to the same channel are used from different threads, and a flush moves `Data` between the fields,
+// so `Data` must be both `Send` and `Sync` for any of them to be sent or shared.
+unsafe impl<Data: Send + Sync> Send for DirectedChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for ReadOnlyDataPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for WritableDataPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for DirectedChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for ReadOnlyDataPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for WritableDataPointer<Data> {}
+
+/// Get references to the `Data` fields pointed to by all given pointers.
+/// All references are obtained with the same [DataKey], hence they all stem from the same phase.
+///
+/// See [`get_all_into`] for a variant that does not allocate.
+pub fn get_all<'a, Data>(
+    pointers: &'a [ReadOnlyDataPointer<Data>],
+    data_key: &'a DataKey,
+) -> Vec<&'a Data> {
+    let mut result = Vec::with_capacity(pointers.len());
+    get_all_into(pointers, data_key, &mut result);
+    result
+}
+
+/// Append references to the `Data` fields pointed to by all given pointers to the given buffer.
+/// This does not allocate if the buffer has enough spare capacity.
+///
+/// See [`get_all`].
+pub fn get_all_into<'a, Data>(
+    pointers: &'a [ReadOnlyDataPointer<Data>],
+    data_key: &'a DataKey,
+    buffer: &mut Vec<&'a Data>,
+) {
+    buffer.extend(pointers.iter().map(|pointer| pointer.get(data_key)));
+}
+
+/// Flush all given channels, or none of them.
+/// First, all channels are asked whether they can be flushed via [`IDirectedChannel::prepare_flush`],
+/// and only if all of them agree, all of them are flushed.
+/// Otherwise, returns the index of the first channel that refused, and no channel is flushed.
+///
+/// With plain [`DirectedChannelPointer`]s, which can always be flushed, this is the same as flushing all channels in order.
+pub fn flush_transaction(
+    channels: &mut [&mut dyn IDirectedChannel],
+    channel_key: &ChannelKey,
+) -> Result<(), usize> {
+    if let Some(index) = channels
+        .iter_mut()
+        .position(|channel| !channel.prepare_flush(channel_key))
+    {
+        return Err(index);
+    }
+    for channel in channels {
+        channel.flush(channel_key);
+    }
+    Ok(())
+}
+
+/// Object-safe trait for [`DirectedChannelPointer`]s.
+pub trait IDirectedChannel: Send + Sync {
+    /// Perform the [`DirectedChannelPointer::flush`] operation.
+    fn flush(&mut self, channel_key: &ChannelKey);
+
+    /// Returns `false` if the channel cannot be flushed right now, e.g. because its writable `Data` is invalid.
+    /// This is used by [`flush_transaction`] to flush either all channels of a group or none of them.
+    /// By default, a channel can always be flushed.
+    fn prepare_flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) -> bool {
+        true
+    }
+}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for DirectedChannelPointer<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        DirectedChannelPointer::flush(self, channel_key);
+    }
+}
+
+/// A directed channel whose writable and read-only `Data` have different types.
+/// It holds a writable instance of `W` and a read-only instance of `R`.
+/// A flush converts the writable `W` into the read-only `R` via `R: From<&W>`.
+///
+/// This allows a writer to produce a rich internal representation while the reader only receives a compact view of it.
+///
+/// See [DirectedChannel2::create] for more info.
+#[derive(Debug)]
+pub struct DirectedChannel2<W, R> {
+    read_only: R,
+    writable: W,
+    version: u64,
+    dirty: bool,
+}
+
+/// A pointer to a directed channel with different writable and read-only types.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [DirectedChannel2::destroy] or [DirectedChannel2Pointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct DirectedChannel2Pointer<W, R> {
+    channel: Box<DirectedChannel2<W, R>>,
+}
+
+impl<W, R> DirectedChannel2<W, R> {
+    /// Create a directed channel and hand out three pointers to it.
+    /// One [DirectedChannel2Pointer] used to flush (convert) the content of the writable `W` field into the read-only `R` field,
+    /// one [ReadOnlyDataPointer] used to read from the directed channel, and
+    /// one [WritableDataPointer] used to write to the directed channel.
+    ///
+    /// See [`DirectedChannel::create`] for more details.
+    pub fn create(
+        read_only: R,
+        writable: W,
+    ) -> (
+        DirectedChannel2Pointer<W, R>,
+        ReadOnlyDataPointer<R>,
+        WritableDataPointer<W>,
+    ) {
+        let mut channel_pointer = DirectedChannel2Pointer {
+            channel: Box::new(DirectedChannel2 {
+                read_only,
+                writable,
+                version: 0,
+                dirty: false,
+            }),
+        };
+        let channel: &mut DirectedChannel2<W, R> = &mut channel_pointer.channel;
+        let read_only_data_pointer = ReadOnlyDataPointer {
+            data: NonNull::from(&channel.read_only),
+            version: (&channel.version) as *const u64,
+        };
+        let writable_data_pointer = WritableDataPointer {
+            data: NonNull::from(&mut channel.writable),
+            dirty: (&mut channel.dirty) as *mut bool,
+        };
+        (
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel2::create]).
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: DirectedChannel2Pointer<W, R>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<R>>,
+        writable_data_pointer: WritableDataPointer<W>,
+    ) -> (R, W) {
+        let DirectedChannel2Pointer { channel } = channel_pointer;
+        if let Some(kind) = check_destroy(
+            (&channel.read_only as *const R).cast(),
+            (&channel.writable as *const W).cast(),
+            0,
+            &mut read_only_data_pointers
+                .into_iter()
+                .map(|read_only_data_pointer| read_only_data_pointer.data.as_ptr() as *const u8),
+            writable_data_pointer.data.as_ptr() as *const u8,
+        ) {
+            panic!("{}", kind);
+        }
+
+        (channel.read_only, channel.writable)
+    }
+}
+
+impl<W, R: for<'a> From<&'a W>> DirectedChannel2Pointer<W, R> {
+    /// Convert the writable `W` into the read-only `R`.
+    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut DirectedChannel2<W, R> = &mut self.channel;
+        channel.read_only = R::from(&channel.writable);
+        channel.version += 1;
+        channel.dirty = false;
+    }
+}
+
+impl<W, R> DirectedChannel2Pointer<W, R> {
+    /// Shorthand for [DirectedChannel2::destroy].
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<R>>,
+        writable_data_pointer: WritableDataPointer<W>,
+    ) -> (R, W) {
+        DirectedChannel2::destroy(self, read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+unsafe impl<W: Send + Sync, R: Send + Sync> Send for DirectedChannel2Pointer<W, R> {}
+
+unsafe impl<W: Send + Sync, R: Send + Sync> Sync for DirectedChannel2Pointer<W, R> {}
+
+impl<W: Send + Sync, R: for<'a> From<&'a W> + Send + Sync> IDirectedChannel
+    for DirectedChannel2Pointer<W, R>
+{
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        DirectedChannel2Pointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
 mod tests {
+    use std::{
+        cell::Cell,
+        collections::HashSet,
+        mem::{self, MaybeUninit},
+        panic::{self, AssertUnwindSafe},
+        ptr,
+        rc::Rc,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
     use crate::{
-        directed::{DirectedChannel, IDirectedChannel},
-        MasterKey,
+        directed::{
+            check_destroy, flush_transaction, get_all, get_all_into, Checkpoint, DestroyError,
+            DestroyErrorKind, DirectedChannel, DirectedChannel2, DirectedChannelPointer,
+            IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer,
+        },
+        ChannelKey, MasterKey,
     };
 
+    /// A type that counts how often it was cloned, and only compares its value.
+    #[derive(Debug)]
+    struct CloneCounter {
+        value: i32,
+        clones: Rc<Cell<usize>>,
+    }
+
+    impl Clone for CloneCounter {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            Self {
+                value: self.value,
+                clones: self.clones.clone(),
+            }
+        }
+    }
+
+    impl PartialEq for CloneCounter {
+        fn eq(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+
     #[test]
     fn test() {
         let mut master_key = unsafe { MasterKey::create_unlimited() };
@@ -255,6 +2178,1712 @@ mod tests {
         assert_eq!(writable_data, 3);
     }
 
+    #[test]
+    fn test_flush_swap() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![1], vec![2]);
+
+        channel_pointer.flush_swap(&master_key.get_channel_key());
+        let data_key = master_key.get_data_key();
+        assert_eq!(*read_only_data_pointer.get(&data_key), vec![2]);
+        assert_eq!(*writable_data_pointer.get(&data_key), vec![1]);
+        writable_data_pointer.get_mut(&data_key).push(3);
+
+        channel_pointer.flush_swap(&data_key.into_channel_key());
+        let data_key = master_key.get_data_key();
+        assert_eq!(*read_only_data_pointer.get(&data_key), vec![1, 3]);
+        assert_eq!(*writable_data_pointer.get(&data_key), vec![2]);
+
+        let (read_only_data, writable_data) = DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert_eq!(read_only_data, vec![1, 3]);
+        assert_eq!(writable_data, vec![2]);
+    }
+
+    #[test]
+    fn test_flush_with() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+
+        for i in 1..4 {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer.get_mut(&data_key) = i;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush_with(&channel_key, |read_only, writable| *read_only += *writable);
+        }
+
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 6);
+        let (read_only_data, writable_data) = DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert_eq!(read_only_data, 6);
+        assert_eq!(writable_data, 3);
+    }
+
+    #[test]
+    fn test_flush_if_changed() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let clones = Rc::new(Cell::new(0));
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(
+                CloneCounter {
+                    value: 0,
+                    clones: clones.clone(),
+                },
+                CloneCounter {
+                    value: 0,
+                    clones: clones.clone(),
+                },
+            );
+
+        assert!(!channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(clones.get(), 0);
+
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .value = 1;
+        assert!(channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(clones.get(), 1);
+        assert_eq!(
+            read_only_data_pointer.get(&master_key.get_data_key()).value,
+            1
+        );
+
+        assert!(!channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(clones.get(), 1);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_flush_reuses_allocation() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(Vec::with_capacity(10), vec![1, 2, 3]);
+        let buffer = read_only_data_pointer
+            .get(&master_key.get_data_key())
+            .as_ptr();
+
+        for i in 4..7 {
+            channel_pointer.flush_in_place(&master_key.get_channel_key());
+            let data_key = master_key.get_data_key();
+            assert_eq!(read_only_data_pointer.get(&data_key).as_ptr(), buffer);
+            assert_eq!(
+                read_only_data_pointer.get(&data_key),
+                writable_data_pointer.get(&data_key)
+            );
+            writable_data_pointer.get_mut(&data_key).push(i);
+        }
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_flush_count() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        assert_eq!(channel_pointer.flush_count(), 0);
+
+        for _ in 0..3 {
+            channel_pointer.flush(&master_key.get_channel_key());
+        }
+        assert_eq!(channel_pointer.flush_count(), 3);
+
+        channel_pointer.flush_swap(&master_key.get_channel_key());
+        assert_eq!(channel_pointer.flush_count(), 4);
+
+        assert!(!channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(channel_pointer.flush_count(), 4);
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        assert!(channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(channel_pointer.flush_count(), 5);
+
+        channel_pointer.flush_with(&master_key.get_channel_key(), |_, _| {});
+        assert_eq!(channel_pointer.flush_count(), 6);
+        assert!(format!("{:?}", channel_pointer).contains("flush_count: 6"));
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_version() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let mut last_version = read_only_data_pointer.version(&master_key.get_data_key());
+        assert_eq!(last_version, 0);
+
+        for i in 1..4 {
+            let data_key = master_key.get_data_key();
+            assert_eq!(read_only_data_pointer.version(&data_key), last_version);
+            *writable_data_pointer.get_mut(&data_key) = i;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush(&channel_key);
+
+            let data_key = channel_key.into_data_key();
+            let version = read_only_data_pointer.version(&data_key);
+            assert_eq!(version, last_version + 1);
+            last_version = version;
+        }
+
+        assert!(!channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(
+            read_only_data_pointer.version(&master_key.get_data_key()),
+            last_version
+        );
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 4;
+        assert!(channel_pointer.flush_if_changed(&master_key.get_channel_key()));
+        assert_eq!(
+            read_only_data_pointer.version(&master_key.get_data_key()),
+            last_version + 1
+        );
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_flush_dirty() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+
+        assert_eq!(*writable_data_pointer.get(&master_key.get_data_key()), 1);
+        assert!(!channel_pointer.flush_dirty(&master_key.get_channel_key()));
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 0);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        assert!(channel_pointer.flush_dirty(&master_key.get_channel_key()));
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 2);
+        assert!(!channel_pointer.flush_dirty(&master_key.get_channel_key()));
+
+        writable_data_pointer.get_mut(&master_key.get_data_key());
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert!(!channel_pointer.flush_dirty(&master_key.get_channel_key()));
+        assert_eq!(channel_pointer.flush_count(), 2);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_flush_copy() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut copy_channel_pointer, copy_read_only_data_pointer, mut copy_writable_data_pointer) =
+            DirectedChannel::create_equal([0.0f32; 16]);
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create_equal([0.0f32; 16]);
+
+        for i in 0..100 {
+            let data_key = master_key.get_data_key();
+            assert_eq!(
+                copy_read_only_data_pointer.get(&data_key),
+                read_only_data_pointer.get(&data_key)
+            );
+            copy_writable_data_pointer.get_mut(&data_key)[i % 16] += i as f32;
+            writable_data_pointer.get_mut(&data_key)[i % 16] += i as f32;
+
+            let channel_key = data_key.into_channel_key();
+            copy_channel_pointer.flush_copy(&channel_key);
+            channel_pointer.flush(&channel_key);
+        }
+
+        let (copy_read_only_data, copy_writable_data) = DirectedChannel::destroy_single(
+            copy_channel_pointer,
+            copy_read_only_data_pointer,
+            copy_writable_data_pointer,
+        );
+        let (read_only_data, writable_data) = DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert_eq!(copy_read_only_data, read_only_data);
+        assert_eq!(copy_writable_data, writable_data);
+        assert_eq!(copy_read_only_data, copy_writable_data);
+    }
+
+    #[test]
+    fn test_flush_transform() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+
+        for i in 1..4 {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer.get_mut(&data_key) = i;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush_transform(&channel_key, |writable| writable * 2);
+
+            let data_key = channel_key.into_data_key();
+            assert_eq!(*read_only_data_pointer.get(&data_key), i * 2);
+            assert_eq!(*writable_data_pointer.get(&data_key), i);
+        }
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        channel_pointer.reset(&master_key.get_channel_key());
+        assert_eq!(*writable_data_pointer.get(&master_key.get_data_key()), 1);
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 1);
+        assert!(!channel_pointer.flush_dirty(&master_key.get_channel_key()));
+        assert_eq!(channel_pointer.flush_count(), 1);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_directed_channel2() {
+        #[derive(Debug, PartialEq)]
+        struct Sum(i32);
+
+        impl From<&(i32, i32)> for Sum {
+            fn from((a, b): &(i32, i32)) -> Self {
+                Self(a + b)
+            }
+        }
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel2::create(Sum(0), (0, 0));
+
+        for i in 1..4 {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer.get_mut(&data_key) = (i, 10 * i);
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush(&channel_key);
+
+            let data_key = channel_key.into_data_key();
+            assert_eq!(*read_only_data_pointer.get(&data_key), Sum(11 * i));
+        }
+
+        let (read_only_data, writable_data) =
+            channel_pointer.destroy([read_only_data_pointer], writable_data_pointer);
+        assert_eq!(read_only_data, Sum(33));
+        assert_eq!(writable_data, (3, 30));
+    }
+
+    #[test]
+    fn test_into_read_only() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+
+        let downgraded_data_pointer = writable_data_pointer.into_read_only();
+        let data_key = master_key.get_data_key();
+        assert_eq!(*downgraded_data_pointer.get(&data_key), 1);
+        assert_eq!(*read_only_data_pointer.get(&data_key), 0);
+
+        channel_pointer.flush(&data_key.into_channel_key());
+        let data_key = master_key.get_data_key();
+        assert_eq!(*downgraded_data_pointer.get(&data_key), 1);
+        assert_eq!(*read_only_data_pointer.get(&data_key), 1);
+        assert_eq!(downgraded_data_pointer.version(&data_key), 0);
+        assert_eq!(read_only_data_pointer.version(&data_key), 1);
+
+        let (read_only_data, writable_data) = DirectedChannel::destroy_downgraded(
+            channel_pointer,
+            [read_only_data_pointer],
+            downgraded_data_pointer,
+        );
+        assert_eq!(read_only_data, 1);
+        assert_eq!(writable_data, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not point to the channel")]
+    fn test_destroy_downgraded_with_wrong_pointer() {
+        let (channel_pointer, read_only_data_pointer, _writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        DirectedChannel::destroy_downgraded(
+            channel_pointer,
+            [read_only_data_pointer],
+            read_only_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_new_reader() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+        let mut read_only_data_pointers = vec![read_only_data_pointer];
+        for _ in 0..3 {
+            read_only_data_pointers.push(channel_pointer.new_reader());
+        }
+
+        channel_pointer.flush(&master_key.get_channel_key());
+        let data_key = master_key.get_data_key();
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&data_key), 1);
+        }
+
+        channel_pointer.destroy(read_only_data_pointers, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_project() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create((0, String::new()), (0, String::new()));
+        let projected_data_pointer =
+            unsafe { read_only_data_pointer.project(&master_key.get_data_key(), |data| &data.1) };
+
+        for i in 1..4 {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer.get_mut(&data_key) = (i, i.to_string());
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush(&channel_key);
+
+            let data_key = channel_key.into_data_key();
+            assert_eq!(*projected_data_pointer.get(&data_key), i.to_string());
+            assert_eq!(projected_data_pointer.version(&data_key), i as u64);
+        }
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must point into the memory")]
+    fn test_project_outside_of_data() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (_channel_pointer, read_only_data_pointer, _writable_data_pointer) =
+            DirectedChannel::create(vec![1], vec![2]);
+        let _ =
+            unsafe { read_only_data_pointer.project(&master_key.get_data_key(), |data| &data[0]) };
+    }
+
+    #[test]
+    fn test_get_all() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut channel_pointers = Vec::new();
+        let mut read_only_data_pointers = Vec::new();
+        let mut writable_data_pointers = Vec::new();
+        for i in 0..4 {
+            let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+                DirectedChannel::create(i, i);
+            channel_pointers.push(channel_pointer);
+            read_only_data_pointers.push(read_only_data_pointer);
+            writable_data_pointers.push(writable_data_pointer);
+        }
+
+        let data_key = master_key.get_data_key();
+        assert_eq!(
+            get_all(&read_only_data_pointers, &data_key),
+            [&0, &1, &2, &3]
+        );
+        let mut buffer = Vec::with_capacity(4);
+        get_all_into(&read_only_data_pointers[2..], &data_key, &mut buffer);
+        assert_eq!(buffer, [&2, &3]);
+
+        for ((channel_pointer, read_only_data_pointer), writable_data_pointer) in channel_pointers
+            .into_iter()
+            .zip(read_only_data_pointers)
+            .zip(writable_data_pointers)
+        {
+            channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+        }
+    }
+
+    #[test]
+    fn test_owns() {
+        let (channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create(0, 0);
+        let (channel_pointer2, read_only_data_pointer2, writable_data_pointer2) =
+            DirectedChannel::create(0, 0);
+
+        assert!(channel_pointer1.owns_read_only(&read_only_data_pointer1));
+        assert!(channel_pointer1.owns_writable(&writable_data_pointer1));
+        assert!(!channel_pointer1.owns_read_only(&read_only_data_pointer2));
+        assert!(!channel_pointer1.owns_writable(&writable_data_pointer2));
+        assert!(channel_pointer2.owns_read_only(&read_only_data_pointer2));
+        assert!(channel_pointer2.owns_writable(&writable_data_pointer2));
+        assert!(!channel_pointer2.owns_read_only(&read_only_data_pointer1));
+        assert!(!channel_pointer2.owns_writable(&writable_data_pointer1));
+
+        channel_pointer1.destroy_single(read_only_data_pointer1, writable_data_pointer1);
+        channel_pointer2.destroy_single(read_only_data_pointer2, writable_data_pointer2);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![0], vec![1]);
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        let snapshot = read_only_data_pointer.snapshot(&master_key.get_data_key());
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .push(2);
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(snapshot, [1]);
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [1, 2]
+        );
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_set_and_modify() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Counter {
+            name: &'static str,
+            count: u32,
+        }
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create_equal(Counter {
+                name: "a",
+                count: 0,
+            });
+
+        writable_data_pointer.set(
+            &master_key.get_data_key(),
+            Counter {
+                name: "b",
+                count: 1,
+            },
+        );
+        assert!(channel_pointer.flush_dirty(&master_key.get_channel_key()));
+        writable_data_pointer.modify(&master_key.get_data_key(), |counter| counter.count += 1);
+        assert!(channel_pointer.flush_dirty(&master_key.get_channel_key()));
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            Counter {
+                name: "b",
+                count: 2
+            }
+        );
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_read_copy() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0.0, 0.0);
+
+        writable_data_pointer.set(&master_key.get_data_key(), 1.5f64);
+        channel_pointer.flush(&master_key.get_channel_key());
+        let data_key = master_key.get_data_key();
+        assert_eq!(read_only_data_pointer.read_copy(&data_key), 1.5);
+        assert_eq!(writable_data_pointer.read_copy(&data_key), 1.5);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_pointer_identity() {
+        let (channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create(0, 0);
+        let (channel_pointer2, read_only_data_pointer2, writable_data_pointer2) =
+            DirectedChannel::create(0, 0);
+
+        let read_only_data_pointers: HashSet<_> = [
+            read_only_data_pointer1,
+            read_only_data_pointer1,
+            channel_pointer1.new_reader(),
+            read_only_data_pointer2,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(read_only_data_pointers.len(), 2);
+        assert!(read_only_data_pointers.contains(&channel_pointer2.new_reader()));
+
+        assert_ne!(writable_data_pointer1, writable_data_pointer2);
+        let writable_data_pointers: HashSet<_> = [&writable_data_pointer1, &writable_data_pointer2]
+            .into_iter()
+            .collect();
+        assert_eq!(writable_data_pointers.len(), 2);
+
+        assert_ne!(channel_pointer1, channel_pointer2);
+        // The reader count of channels is interior mutable, but does not affect their identity-based hash.
+        #[allow(clippy::mutable_key_type)]
+        let channel_pointers: HashSet<_> =
+            [&channel_pointer1, &channel_pointer2, &channel_pointer1]
+                .into_iter()
+                .collect();
+        assert_eq!(channel_pointers.len(), 2);
+
+        channel_pointer1.destroy([read_only_data_pointer1; 2], writable_data_pointer1);
+        channel_pointer2.destroy([read_only_data_pointer2; 2], writable_data_pointer2);
+    }
+
+    #[test]
+    fn test_debug_value() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create("read-only value", "writable value");
+
+        let data_key = master_key.get_data_key();
+        assert!(
+            format!("{:?}", read_only_data_pointer.debug_value(&data_key))
+                .contains("read-only value")
+        );
+        assert!(
+            format!("{:?}", writable_data_pointer.debug_value(&data_key))
+                .contains("writable value")
+        );
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_raw_pointers() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+
+        assert_eq!(
+            read_only_data_pointer.as_ptr(),
+            read_only_data_pointer.data.as_ptr()
+        );
+        assert_eq!(
+            writable_data_pointer.as_mut_ptr(),
+            writable_data_pointer.data.as_ptr()
+        );
+        let data_key = master_key.get_data_key();
+        assert!(ptr::eq(
+            read_only_data_pointer.as_ptr(),
+            read_only_data_pointer.get(&data_key)
+        ));
+        unsafe { *writable_data_pointer.as_mut_ptr() = 2 };
+        assert_eq!(*writable_data_pointer.get(&data_key), 2);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_raw_channel_pointer() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(vec![0], vec![1]);
+
+        let raw_channel_pointer = channel_pointer.into_raw();
+        let mut channel_pointer = unsafe { DirectedChannelPointer::from_raw(raw_channel_pointer) };
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), [1]);
+
+        let (read_only_data, writable_data) = DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert_eq!(read_only_data, [1]);
+        assert_eq!(writable_data, [1]);
+    }
+
+    #[test]
+    fn test_borrow_pointers() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+
+        for i in 0..3 {
+            let (borrowed_read_only_data_pointer, mut borrowed_writable_data_pointer) =
+                channel_pointer.borrow_pointers(&mut writable_data_pointer);
+            let data_key = master_key.get_data_key();
+            thread::scope(|scope| {
+                scope.spawn(|| assert_eq!(*borrowed_read_only_data_pointer.get(&data_key), i));
+                scope.spawn(|| *borrowed_writable_data_pointer.get_mut(&data_key) = i + 1);
+            });
+
+            channel_pointer.flush(&data_key.into_channel_key());
+        }
+
+        let (read_only_data, writable_data) = DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert_eq!(read_only_data, 3);
+        assert_eq!(writable_data, 3);
+    }
+
+    #[test]
+    fn test_get_disjoint_mut() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Pair {
+            left: u32,
+            right: String,
+        }
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let data = Pair {
+            left: 0,
+            right: String::new(),
+        };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create_equal(data);
+
+        let data_key = master_key.get_data_key();
+        let (left, right) = unsafe {
+            writable_data_pointer.get_disjoint_mut(
+                &data_key,
+                |pair| ptr::addr_of_mut!((*pair).left),
+                |pair| ptr::addr_of_mut!((*pair).right),
+            )
+        };
+        *left += 1;
+        right.push('a');
+        channel_pointer.flush_dirty(&data_key.into_channel_key());
+
+        let expected = Pair {
+            left: 1,
+            right: "a".to_string(),
+        };
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            expected
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must not overlap")]
+    fn test_get_disjoint_mut_overlapping() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (_channel_pointer, _read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create((0u32, 0u32), (0u32, 0u32));
+
+        unsafe {
+            writable_data_pointer.get_disjoint_mut(
+                &master_key.get_data_key(),
+                |pair| ptr::addr_of_mut!((*pair).0),
+                |pair| ptr::addr_of_mut!((*pair).0),
+            )
+        };
+    }
+
+    #[test]
+    fn test_create_uninit() {
+        const N: usize = 1 << 16;
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::<[u8; N]>::create_uninit();
+
+        let writable = writable_data_pointer.get_mut(&master_key.get_data_key());
+        unsafe { (writable.as_mut_ptr() as *mut u8).write_bytes(7, N) };
+        channel_pointer.flush_copy(&master_key.get_channel_key());
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            unsafe { channel_pointer.assume_init(read_only_data_pointer, writable_data_pointer) };
+        assert_eq!(channel_pointer.flush_count(), 1);
+
+        let data_key = master_key.get_data_key();
+        assert!(read_only_data_pointer
+            .get(&data_key)
+            .iter()
+            .all(|byte| *byte == 7));
+        writable_data_pointer.get_mut(&data_key)[0] = 8;
+        channel_pointer.flush(&data_key.into_channel_key());
+        assert_eq!(read_only_data_pointer.get(&master_key.get_data_key())[0], 8);
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_create_uninit_with_drop() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::<Vec<u8>>::create_uninit();
+
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .write(vec![1, 2, 3]);
+        channel_pointer.flush_transform(&master_key.get_channel_key(), |writable| {
+            MaybeUninit::new(unsafe { writable.assume_init_ref() }.clone())
+        });
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) = unsafe {
+            DirectedChannel::assume_init(
+                channel_pointer,
+                read_only_data_pointer,
+                writable_data_pointer,
+            )
+        };
+
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            DirectedChannel::destroy_single(
+                channel_pointer,
+                read_only_data_pointer,
+                writable_data_pointer,
+            ),
+            (vec![1, 2, 3], vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "only 1 of 2 read-only data pointers were returned")]
+    fn test_assume_init_missing_readers() {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::<u8>::create_uninit();
+        let _ = channel_pointer.new_reader();
+        let _ = unsafe {
+            DirectedChannel::assume_init(
+                channel_pointer,
+                read_only_data_pointer,
+                writable_data_pointer,
+            )
+        };
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_create_zeroed() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::<[u64; 1024]>::create_zeroed();
+
+        let data_key = master_key.get_data_key();
+        assert!(read_only_data_pointer
+            .get(&data_key)
+            .iter()
+            .all(|value| *value == 0));
+        writable_data_pointer.get_mut(&data_key)[1023] = 1;
+        channel_pointer.flush(&data_key.into_channel_key());
+        assert_eq!(
+            read_only_data_pointer.get(&master_key.get_data_key())[1023],
+            1
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_flush_pod() {
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct Vertex {
+            position: [f32; 3],
+            color: u32,
+        }
+
+        unsafe impl bytemuck::Zeroable for Vertex {}
+        unsafe impl bytemuck::Pod for Vertex {}
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::<Vertex>::create_zeroed();
+
+        let vertex = Vertex {
+            position: [1.0, 2.0, 3.0],
+            color: 0xff00ff00,
+        };
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = vertex;
+        channel_pointer.flush_pod(&master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        assert_eq!(*read_only_data_pointer.get(&data_key), vertex);
+        assert_eq!(
+            bytemuck::bytes_of(read_only_data_pointer.get(&data_key)),
+            bytemuck::bytes_of(&vertex)
+        );
+        assert_eq!(channel_pointer.flush_count(), 1);
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[cfg(all(feature = "leak-check", not(feature = "leak-check-abort")))]
+    #[test]
+    fn test_leak_check() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct LeakLogger;
+
+        static LEAK_WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+        impl log::Log for LeakLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                if record
+                    .args()
+                    .to_string()
+                    .contains("dropped without being destroyed")
+                {
+                    LEAK_WARNINGS.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        log::set_logger(&LeakLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        let leaked_before = LEAK_WARNINGS.load(Ordering::Relaxed);
+
+        let (channel_pointer, _read_only_data_pointer, _writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        drop(channel_pointer);
+        assert!(LEAK_WARNINGS.load(Ordering::Relaxed) > leaked_before);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_trace_flush() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert!(logs_contain("flush"));
+        assert!(logs_contain("version=1"));
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert!(logs_contain("destroyed"));
+    }
+
+    #[test]
+    fn test_try_destroy() {
+        let (channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create(1, 2);
+        let (channel_pointer2, read_only_data_pointer2, mut writable_data_pointer2) =
+            DirectedChannel::create(3, 4);
+        let writable_address2 = writable_data_pointer2.as_mut_ptr() as usize;
+
+        let error = DirectedChannel::try_destroy(
+            channel_pointer1,
+            [read_only_data_pointer1],
+            writable_data_pointer2,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.kind,
+            DestroyErrorKind::WritableMismatch {
+                address: writable_address2
+            }
+        );
+        let DestroyError {
+            channel_pointer: channel_pointer1,
+            read_only_data_pointers,
+            writable_data_pointer: writable_data_pointer2,
+            ..
+        } = error;
+        assert_eq!(read_only_data_pointers, [read_only_data_pointer1]);
+
+        let error = DirectedChannel::try_destroy(
+            channel_pointer2,
+            [
+                read_only_data_pointer2,
+                read_only_data_pointer1,
+                read_only_data_pointer2,
+            ],
+            writable_data_pointer2,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.kind,
+            DestroyErrorKind::ReadOnlyMismatch {
+                address: read_only_data_pointer1.as_ptr() as usize
+            }
+        );
+        assert_eq!(
+            error.read_only_data_pointers,
+            [
+                read_only_data_pointer2,
+                read_only_data_pointer1,
+                read_only_data_pointer2
+            ]
+        );
+
+        assert_eq!(
+            DirectedChannel::try_destroy(
+                error.channel_pointer,
+                [read_only_data_pointer2],
+                error.writable_data_pointer
+            )
+            .unwrap(),
+            (3, 4)
+        );
+        assert_eq!(
+            DirectedChannel::try_destroy(
+                channel_pointer1,
+                [read_only_data_pointer1],
+                writable_data_pointer1
+            )
+            .unwrap(),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn test_check_destroy() {
+        let fields = [0u8; 2];
+        let (read_only, writable) = (&fields[0] as *const u8, &fields[1] as *const u8);
+        assert_eq!(
+            check_destroy(
+                read_only,
+                writable,
+                2,
+                &mut [read_only; 2].into_iter(),
+                writable
+            ),
+            None
+        );
+        assert_eq!(
+            check_destroy(
+                read_only,
+                writable,
+                1,
+                &mut [read_only].into_iter(),
+                read_only
+            ),
+            Some(DestroyErrorKind::WritableMismatch {
+                address: read_only as usize
+            })
+        );
+        assert_eq!(
+            check_destroy(
+                read_only,
+                writable,
+                1,
+                &mut [writable].into_iter(),
+                writable
+            ),
+            Some(DestroyErrorKind::ReadOnlyMismatch {
+                address: writable as usize
+            })
+        );
+        assert_eq!(
+            check_destroy(
+                read_only,
+                writable,
+                3,
+                &mut [read_only].into_iter(),
+                writable
+            ),
+            Some(DestroyErrorKind::MissingReaders {
+                expected: 3,
+                returned: 1
+            })
+        );
+    }
+
+    #[cfg(feature = "cache-padded")]
+    #[test]
+    fn test_cache_padded() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0u8, 0u8);
+
+        let read_only_address = read_only_data_pointer.data.as_ptr() as usize;
+        let writable_address = writable_data_pointer.data.as_ptr() as usize;
+        assert_eq!(read_only_address % 64, 0);
+        assert_eq!(writable_address % 64, 0);
+        assert!(read_only_address.abs_diff(writable_address) >= 64);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn test_destroy_unchecked() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut destroy = |unchecked: bool| {
+            let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+                DirectedChannel::create(vec![1], vec![2]);
+            writable_data_pointer
+                .get_mut(&master_key.get_data_key())
+                .push(3);
+            channel_pointer.flush(&master_key.get_channel_key());
+
+            if unchecked {
+                unsafe {
+                    DirectedChannel::destroy_unchecked(channel_pointer, writable_data_pointer)
+                }
+            } else {
+                DirectedChannel::destroy_single(
+                    channel_pointer,
+                    read_only_data_pointer,
+                    writable_data_pointer,
+                )
+            }
+        };
+
+        assert_eq!(destroy(true), destroy(false));
+        assert_eq!(destroy(true), (vec![2, 3], vec![2, 3]));
+    }
+
+    #[test]
+    fn test_flush_transaction() {
+        /// Refuses to be flushed if its writable `Data` is negative.
+        struct NonNegative(DirectedChannelPointer<i32>);
+
+        impl IDirectedChannel for NonNegative {
+            fn flush(&mut self, channel_key: &ChannelKey) {
+                self.0.flush(channel_key);
+            }
+
+            fn prepare_flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) -> bool {
+                *self.0.channel.writable >= 0
+            }
+        }
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create(0, 1);
+        let (mut channel_pointer2, read_only_data_pointer2, writable_data_pointer2) =
+            DirectedChannel::create("", "two");
+        let (channel_pointer3, read_only_data_pointer3, mut writable_data_pointer3) =
+            DirectedChannel::create(0, 3);
+        let mut channel_pointer3 = NonNegative(channel_pointer3);
+
+        let result = flush_transaction(
+            &mut [
+                &mut channel_pointer1,
+                &mut channel_pointer2,
+                &mut channel_pointer3,
+            ],
+            &master_key.get_channel_key(),
+        );
+        assert_eq!(result, Ok(()));
+        {
+            let data_key = master_key.get_data_key();
+            assert_eq!(*read_only_data_pointer1.get(&data_key), 1);
+            assert_eq!(*read_only_data_pointer2.get(&data_key), "two");
+            assert_eq!(*read_only_data_pointer3.get(&data_key), 3);
+        }
+
+        *writable_data_pointer3.get_mut(&master_key.get_data_key()) = -3;
+        let result = flush_transaction(
+            &mut [
+                &mut channel_pointer1,
+                &mut channel_pointer2,
+                &mut channel_pointer3,
+            ],
+            &master_key.get_channel_key(),
+        );
+        assert_eq!(result, Err(2));
+        assert_eq!(channel_pointer1.flush_count(), 1);
+        assert_eq!(*read_only_data_pointer3.get(&master_key.get_data_key()), 3);
+
+        channel_pointer1.destroy_single(read_only_data_pointer1, writable_data_pointer1);
+        channel_pointer2.destroy_single(read_only_data_pointer2, writable_data_pointer2);
+        channel_pointer3
+            .0
+            .destroy_single(read_only_data_pointer3, writable_data_pointer3);
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+        channel_pointer.flush(&master_key.get_channel_key());
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        let checkpoint = channel_pointer.checkpoint(&master_key.get_data_key());
+        assert_eq!(
+            checkpoint,
+            Checkpoint {
+                read_only: 1,
+                writable: 2
+            }
+        );
+
+        for i in 3..6 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = i;
+            channel_pointer.flush(&master_key.get_channel_key());
+        }
+        channel_pointer.restore(checkpoint.clone(), &master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        assert_eq!(*read_only_data_pointer.get(&data_key), checkpoint.read_only);
+        assert_eq!(*writable_data_pointer.get(&data_key), checkpoint.writable);
+        assert_eq!(channel_pointer.flush_count(), 5);
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert!(!channel_pointer.is_frozen());
+        channel_pointer.freeze();
+        assert!(channel_pointer.is_frozen());
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            channel_pointer.flush(&master_key.get_channel_key())
+        }));
+        assert!(result.is_err());
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 1);
+        assert_eq!(channel_pointer.flush_count(), 1);
+
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_read_only_mode() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        let channel_pointer = channel_pointer.into_read_only_mode(writable_data_pointer);
+        let read_only_data_pointers = [
+            read_only_data_pointer,
+            channel_pointer.new_reader(),
+            channel_pointer.new_reader(),
+        ];
+        assert_eq!(channel_pointer.reader_count(), 3);
+        let data_key = master_key.get_data_key();
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&data_key), 1);
+        }
+
+        assert_eq!(channel_pointer.destroy(read_only_data_pointers), (1, 1));
+    }
+
+    #[test]
+    fn test_flush_if_version() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let version = read_only_data_pointer.version(&master_key.get_data_key());
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        assert_eq!(
+            channel_pointer.flush_if_version(&master_key.get_channel_key(), version),
+            Ok(version + 1)
+        );
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        assert_eq!(
+            channel_pointer.flush_if_version(&master_key.get_channel_key(), version),
+            Err(version + 1)
+        );
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 1);
+
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_deep_clone() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![1], vec![2]);
+        let (clone_channel_pointer, clone_read_only_data_pointer, clone_writable_data_pointer) =
+            channel_pointer.deep_clone(&master_key.get_data_key());
+
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .push(3);
+        channel_pointer.flush(&master_key.get_channel_key());
+        let data_key = master_key.get_data_key();
+        assert_eq!(*read_only_data_pointer.get(&data_key), [2, 3]);
+        assert_eq!(*clone_read_only_data_pointer.get(&data_key), [1]);
+        assert_eq!(*clone_writable_data_pointer.get(&data_key), [2]);
+
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+        assert_eq!(
+            clone_channel_pointer
+                .destroy_single(clone_read_only_data_pointer, clone_writable_data_pointer),
+            (vec![1], vec![2])
+        );
+    }
+
+    #[test]
+    fn test_destroy_readers() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+        let read_only_data_pointers = [
+            read_only_data_pointer,
+            channel_pointer.new_reader(),
+            channel_pointer.new_reader(),
+            channel_pointer.new_reader(),
+        ];
+
+        channel_pointer.flush(&master_key.get_channel_key());
+        let data_key = master_key.get_data_key();
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&data_key), 1);
+        }
+
+        assert_eq!(
+            channel_pointer.destroy_readers(&read_only_data_pointers, writable_data_pointer),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn test_destroy_with_missing_reader() {
+        let (channel_pointer, read_only_data_pointer1, writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let read_only_data_pointer2 = channel_pointer.new_reader();
+        assert_eq!(channel_pointer.reader_count(), 2);
+
+        let error = DirectedChannel::try_destroy(
+            channel_pointer,
+            [read_only_data_pointer1],
+            writable_data_pointer,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.kind,
+            DestroyErrorKind::MissingReaders {
+                expected: 2,
+                returned: 1
+            }
+        );
+
+        DirectedChannel::destroy(
+            error.channel_pointer,
+            [read_only_data_pointer1, read_only_data_pointer2],
+            error.writable_data_pointer,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "only 0 of 1 read-only data pointers were returned")]
+    fn test_destroy_downgraded_with_missing_reader() {
+        let (channel_pointer, _read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        DirectedChannel::destroy_downgraded(
+            channel_pointer,
+            [],
+            writable_data_pointer.into_read_only(),
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "the channel was already destroyed")]
+    fn test_from_raw_destroyed_channel() {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let raw = channel_pointer.into_raw();
+        let channel_pointer = unsafe { DirectedChannelPointer::from_raw(raw) };
+
+        // The allocation of the destroyed channel is kept, so the aliasing raw pointer does not dangle,
+        // and the check never reads freed memory.
+        let mut freed = DirectedChannel::destroy_boxed(
+            channel_pointer,
+            [read_only_data_pointer],
+            writable_data_pointer,
+        );
+        assert!(ptr::eq(&*freed, raw));
+        let _ = unsafe { DirectedChannelPointer::from_raw(&mut *freed) };
+    }
+
+    #[test]
+    fn test_pointer_niche() {
+        assert_eq!(
+            mem::size_of::<Option<ReadOnlyDataPointer<u8>>>(),
+            mem::size_of::<ReadOnlyDataPointer<u8>>()
+        );
+        assert_eq!(
+            mem::size_of::<Option<WritableDataPointer<u8>>>(),
+            mem::size_of::<WritableDataPointer<u8>>()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_value() {
+        #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Position {
+            x: i32,
+            y: i32,
+        }
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create_equal(Position { x: 0, y: 0 });
+
+        writable_data_pointer.set(&master_key.get_data_key(), Position { x: 1, y: -2 });
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        let mut json = Vec::new();
+        read_only_data_pointer
+            .serialize_value(&data_key, &mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+        assert_eq!(json, br#"{"x":1,"y":-2}"#);
+        let position: Position = serde_json::from_slice(&json).unwrap();
+        assert_eq!(position, read_only_data_pointer.snapshot(&data_key));
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create([0u64; 4], [0u64; 4]);
+        let stats = channel_pointer.stats();
+        assert_eq!(stats.flush_count, 0);
+        assert_eq!(stats.last_flush, None);
+        assert_eq!(stats.bytes_copied, 0);
+
+        let before = std::time::Instant::now();
+        for _ in 0..3 {
+            channel_pointer.flush(&master_key.get_channel_key());
+        }
+        let stats = channel_pointer.stats();
+        assert_eq!(stats.flush_count, 3);
+        assert!(stats.last_flush.unwrap() >= before);
+        assert_eq!(stats.bytes_copied, 3 * 32);
+
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_create_equal_with() {
+        struct Counted(usize);
+
+        let constructed = Cell::new(0);
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create_equal_with(|| {
+                constructed.set(constructed.get() + 1);
+                Counted(7)
+            });
+        assert_eq!(constructed.get(), 2);
+
+        let (read_only, writable) =
+            channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+        assert_eq!(read_only.0, 7);
+        assert_eq!(writable.0, 7);
+    }
+
+    #[test]
+    fn test_create_default() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::<Vec<u8>>::create_default();
+        let data_key = master_key.get_data_key();
+        assert_eq!(read_only_data_pointer.get(&data_key), &Vec::<u8>::default());
+        assert_eq!(writable_data_pointer.get(&data_key), &Vec::<u8>::default());
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+
+        let channel = DirectedChannel::<u32>::default();
+        assert_eq!(*channel.read_only, 0);
+        assert_eq!(*channel.writable, 0);
+        assert_eq!(channel.flush_count(), 0);
+    }
+
+    #[test]
+    fn test_owned_snapshot() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(String::new(), String::new());
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .push_str("published");
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        let snapshot = read_only_data_pointer.owned_snapshot(&master_key.get_data_key());
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+
+        let handle = thread::spawn(move || {
+            assert_eq!(snapshot.version(), 1);
+            snapshot.len()
+        });
+        assert_eq!(handle.join().unwrap(), 9);
+    }
+
+    #[test]
+    fn test_observers() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let observed1 = Arc::new(Mutex::new(Vec::new()));
+        let observed2 = Arc::new(Mutex::new(Vec::new()));
+        let id1 = channel_pointer.on_flush(Box::new({
+            let observed1 = observed1.clone();
+            move |data: &i32| observed1.lock().unwrap().push(*data)
+        }));
+        channel_pointer.on_flush(Box::new({
+            let observed2 = observed2.clone();
+            move |data: &i32| observed2.lock().unwrap().push(*data * 10)
+        }));
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(*observed1.lock().unwrap(), [1]);
+        assert_eq!(*observed2.lock().unwrap(), [10]);
+
+        assert!(channel_pointer.remove_observer(id1).is_some());
+        assert!(channel_pointer.remove_observer(id1).is_none());
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(*observed1.lock().unwrap(), [1]);
+        assert_eq!(*observed2.lock().unwrap(), [10, 20]);
+
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_create_with_readers() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointers, mut writable_data_pointer) =
+            DirectedChannel::create_with_readers(0, 0, 5);
+        assert_eq!(read_only_data_pointers.len(), 5);
+        assert_eq!(channel_pointer.reader_count(), 5);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 3;
+        channel_pointer.flush(&master_key.get_channel_key());
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 3);
+        }
+
+        channel_pointer.destroy(read_only_data_pointers, writable_data_pointer);
+    }
+
+    #[test]
+    #[should_panic(expected = "read-only data pointers")]
+    fn test_create_with_readers_missing_reader() {
+        let (channel_pointer, mut read_only_data_pointers, writable_data_pointer) =
+            DirectedChannel::create_with_readers(0, 0, 5);
+        read_only_data_pointers.pop();
+        channel_pointer.destroy(read_only_data_pointers, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_destroy_tracked() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let read_only_data_pointers = [
+            read_only_data_pointer,
+            channel_pointer.new_reader(),
+            channel_pointer.new_reader(),
+        ];
+        assert_eq!(channel_pointer.reader_count(), 3);
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        channel_pointer.flush(&master_key.get_channel_key());
+        for read_only_data_pointer in &read_only_data_pointers {
+            assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 2);
+        }
+
+        assert_eq!(
+            unsafe { channel_pointer.destroy_tracked(writable_data_pointer) },
+            (2, 2)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "writable data pointer")]
+    fn test_destroy_tracked_foreign_writable() {
+        let (channel_pointer1, _, writable_data_pointer1) = DirectedChannel::create(0, 0);
+        let (channel_pointer2, _, writable_data_pointer2) = DirectedChannel::create(0, 0);
+        unsafe {
+            channel_pointer2.destroy_tracked(writable_data_pointer1);
+            channel_pointer1.destroy_tracked(writable_data_pointer2);
+        }
+    }
+
+    /// A type whose clone panics for negative values.
+    #[derive(Debug, PartialEq)]
+    struct FragileClone(i32);
+
+    impl Clone for FragileClone {
+        fn clone(&self) -> Self {
+            assert!(self.0 >= 0, "cannot clone a negative value");
+            Self(self.0)
+        }
+    }
+
+    #[test]
+    fn test_panicking_clone_keeps_read_only() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(
+                vec![FragileClone(1), FragileClone(2)],
+                vec![FragileClone(1), FragileClone(2)],
+            );
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) =
+            vec![FragileClone(3), FragileClone(-1)];
+
+        let channel_key = master_key.get_channel_key();
+        assert!(
+            panic::catch_unwind(AssertUnwindSafe(|| channel_pointer.flush(&channel_key))).is_err()
+        );
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+            channel_pointer.flush_if_changed(&channel_key)
+        }))
+        .is_err());
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [FragileClone(1), FragileClone(2)]
+        );
+        assert_eq!(channel_pointer.flush_count(), 0);
+
+        writable_data_pointer.get_mut(&master_key.get_data_key())[1] = FragileClone(4);
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [FragileClone(3), FragileClone(4)]
+        );
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
+    #[test]
+    fn test_recreate() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(vec![1], vec![2]);
+        let read_only_data_pointer2 = channel_pointer.new_reader();
+        channel_pointer.flush(&master_key.get_channel_key());
+        let address = read_only_data_pointer.as_ptr();
+        let freed = channel_pointer.destroy_boxed(
+            [read_only_data_pointer, read_only_data_pointer2],
+            writable_data_pointer,
+        );
+
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::recreate(freed, vec![3], vec![4]);
+        assert_eq!(read_only_data_pointer.as_ptr(), address);
+        assert_eq!(channel_pointer.reader_count(), 1);
+        assert_eq!(channel_pointer.flush_count(), 0);
+        assert_eq!(
+            read_only_data_pointer.version(&master_key.get_data_key()),
+            0
+        );
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), [3]);
+
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), [4]);
+        assert_eq!(
+            channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer),
+            (vec![4], vec![4])
+        );
+    }
+
+    #[test]
+    fn test_recreate_fresh_box() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::recreate(Box::new(DirectedChannel::new(1, 2)), 3, 4);
+        assert_eq!(channel_pointer.reader_count(), 1);
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(*read_only_data_pointer.get(&master_key.get_data_key()), 4);
+        channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+    }
+
     #[test]
     fn ensure_channel_is_object_safe() {
         let mut master_key = unsafe { MasterKey::create_unlimited() };
@@ -0,0 +1,210 @@
+//! A fan-in two-phase channel.
+//! The channel provides multiple writable data pointers and one read-only data pointer.
+//! On flush, the data of all writable ends is merged into the read-only end.
+
+use std::ptr::{self, NonNull};
+
+use crate::{
+    directed::{ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey,
+};
+
+/// A fan-in channel used for communication between threads.
+/// It holds multiple writable instances of `Data` and one read-only instance of `Data`.
+/// A flush merges all writable `Data`s into the read-only `Data`.
+///
+/// This allows multiple threads to each produce a partial result that is combined for a single reader.
+///
+/// See [FanInChannel::create] for more info.
+#[derive(Debug)]
+pub struct FanInChannel<Data> {
+    read_only: Data,
+    version: u64,
+    writable: Vec<Data>,
+    dirty: Vec<bool>,
+}
+
+/// A pointer to a fan-in channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [FanInChannel::destroy] or [FanInChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct FanInChannelPointer<Data> {
+    channel: Box<FanInChannel<Data>>,
+}
+
+impl<Data: Clone> FanInChannel<Data> {
+    /// Create a fan-in channel with `n` writers and hand out pointers to it.
+    /// One [FanInChannelPointer] used to merge the content of the writable `Data` fields into the read-only data field,
+    /// `n` [WritableDataPointer]s used to write to the fan-in channel, each pointing to its own writable `Data` field, and
+    /// one [ReadOnlyDataPointer] used to read from the fan-in channel.
+    ///
+    /// All writable `Data` fields are initialised as clones of `writable`.
+    pub fn create(
+        n: usize,
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        FanInChannelPointer<Data>,
+        Vec<WritableDataPointer<Data>>,
+        ReadOnlyDataPointer<Data>,
+    ) {
+        let mut channel_pointer = FanInChannelPointer {
+            channel: Box::new(FanInChannel {
+                read_only,
+                version: 0,
+                writable: vec![writable; n],
+                dirty: vec![false; n],
+            }),
+        };
+        let channel: &mut FanInChannel<Data> = &mut channel_pointer.channel;
+        let writable_data_pointers = channel
+            .writable
+            .iter_mut()
+            .zip(&mut channel.dirty)
+            .map(|(writable, dirty)| WritableDataPointer {
+                data: NonNull::from(writable),
+                dirty: dirty as *mut bool,
+            })
+            .collect();
+        let read_only_data_pointer = ReadOnlyDataPointer {
+            data: NonNull::from(&channel.read_only),
+            version: (&channel.version) as *const u64,
+        };
+        (
+            channel_pointer,
+            writable_data_pointers,
+            read_only_data_pointer,
+        )
+    }
+}
+
+impl<Data> FanInChannel<Data> {
+    /// Destroys the fan-in channel linked with the given pointers (see [FanInChannel::create]).
+    /// Returns the writable `Data`s in the order of the [WritableDataPointer]s returned by [FanInChannel::create], and the read-only `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: FanInChannelPointer<Data>,
+        writable_data_pointers: impl IntoIterator<Item = WritableDataPointer<Data>>,
+        read_only_data_pointer: ReadOnlyDataPointer<Data>,
+    ) -> (Vec<Data>, Data) {
+        let FanInChannelPointer { channel } = channel_pointer;
+        let ReadOnlyDataPointer {
+            data: read_only_data_pointer,
+            ..
+        } = read_only_data_pointer;
+        assert!(ptr::eq(&channel.read_only, read_only_data_pointer.as_ptr()));
+
+        for writable_data_pointer in writable_data_pointers {
+            let WritableDataPointer {
+                data: writable_data_pointer,
+                ..
+            } = writable_data_pointer;
+            assert!(channel
+                .writable
+                .iter()
+                .any(|writable| ptr::eq(writable, writable_data_pointer.as_ptr())));
+        }
+
+        (channel.writable, channel.read_only)
+    }
+}
+
+impl<Data> FanInChannelPointer<Data> {
+    /// Merge all writable `Data`s into the read-only `Data` using the given closure.
+    /// The closure is called once per writable `Data`, in the order of the [WritableDataPointer]s returned by [FanInChannel::create].
+    /// It receives the read-only `Data` mutably as first argument, and the writable `Data` as second argument.
+    pub fn flush_merge(
+        &mut self,
+        #[allow(unused)] channel_key: &ChannelKey,
+        merge: impl Fn(&mut Data, &Data),
+    ) {
+        let channel: &mut FanInChannel<Data> = &mut self.channel;
+        for writable in &channel.writable {
+            merge(&mut channel.read_only, writable);
+        }
+        channel.version += 1;
+        channel.dirty.iter_mut().for_each(|dirty| *dirty = false);
+    }
+
+    /// The number of writers of this fan-in channel.
+    pub fn writer_count(&self) -> usize {
+        self.channel.writable.len()
+    }
+
+    /// Shorthand for [FanInChannel::destroy].
+    pub fn destroy(
+        self,
+        writable_data_pointers: impl IntoIterator<Item = WritableDataPointer<Data>>,
+        read_only_data_pointer: ReadOnlyDataPointer<Data>,
+    ) -> (Vec<Data>, Data) {
+        FanInChannel::destroy(self, writable_data_pointers, read_only_data_pointer)
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for FanInChannelPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for FanInChannelPointer<Data> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fan_in::FanInChannel, MasterKey};
+
+    #[test]
+    fn test() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut writable_data_pointers, read_only_data_pointer) =
+            FanInChannel::create(3, 0, 0);
+        assert_eq!(channel_pointer.writer_count(), 3);
+
+        let data_key = master_key.get_data_key();
+        for (i, writable_data_pointer) in writable_data_pointers.iter_mut().enumerate() {
+            *writable_data_pointer.get_mut(&data_key) = i + 1;
+        }
+
+        let channel_key = data_key.into_channel_key();
+        channel_pointer.flush_merge(&channel_key, |read_only, writable| *read_only += writable);
+
+        let data_key = channel_key.into_data_key();
+        assert_eq!(*read_only_data_pointer.get(&data_key), 6);
+        assert_eq!(read_only_data_pointer.version(&data_key), 1);
+
+        let (writable_data, read_only_data) = FanInChannel::destroy(
+            channel_pointer,
+            writable_data_pointers,
+            read_only_data_pointer,
+        );
+        assert_eq!(writable_data, [1, 2, 3]);
+        assert_eq!(read_only_data, 6);
+    }
+
+    #[test]
+    fn test_merge_order() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut writable_data_pointers, read_only_data_pointer) =
+            FanInChannel::create(3, String::new(), String::new());
+
+        let data_key = master_key.get_data_key();
+        for (writable_data_pointer, value) in writable_data_pointers.iter_mut().zip(["a", "b", "c"])
+        {
+            writable_data_pointer.get_mut(&data_key).push_str(value);
+        }
+
+        let channel_key = data_key.into_channel_key();
+        channel_pointer.flush_merge(&channel_key, |read_only, writable| {
+            read_only.push_str(writable)
+        });
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            "abc"
+        );
+
+        FanInChannel::destroy(
+            channel_pointer,
+            writable_data_pointers,
+            read_only_data_pointer,
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! A C ABI for directed channels of byte buffers.
+//! This module is only available with the `ffi` feature.
+//!
+//! A channel is created with [`tpc_create`] and must be destroyed with [`tpc_destroy`].
+//! Each channel handle owns its own [`MasterKey`], so the phases of different handles are independent.
+//! Since the keys of this crate cannot cross the FFI boundary, the caller is responsible for the phases of a handle:
+//! the buffers returned by [`tpc_readonly_ptr`] and [`tpc_writable_ptr`] must not be accessed during [`tpc_flush`].
+//!
+//! There are no functions to create keys.
+//! A [`DataKey`](crate::DataKey) or [`ChannelKey`](crate::ChannelKey) is a zero-sized proof that borrows the [`MasterKey`],
+//! and C cannot express that borrow, so an opaque key handle would not prove anything.
+//! Checking the phases at runtime instead would add a cost that the keys exist to avoid.
+//!
+//! [`tpc_len`], [`tpc_readonly_ptr`] and [`tpc_writable_ptr`] only read the handle,
+//! so they may be called concurrently with each other, but not with [`tpc_flush`] or [`tpc_destroy`].
+
+use std::ptr;
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    MasterKey,
+};
+
+/// An opaque handle to a directed channel of a byte buffer, together with the key to access it.
+pub struct TpcChannel {
+    master_key: MasterKey,
+    channel_pointer: DirectedChannelPointer<Vec<u8>>,
+    read_only_data_pointer: ReadOnlyDataPointer<Vec<u8>>,
+    writable_data_pointer: WritableDataPointer<Vec<u8>>,
+    /// The length of both buffers, which never changes.
+    len: usize,
+    /// The read-only buffer, which [`tpc_flush`] copies into instead of replacing it.
+    read_only: *const u8,
+    /// The writable buffer, which is never replaced.
+    writable: *mut u8,
+}
+
+/// Create a directed channel of two zeroed buffers of `len` bytes each.
+/// The returned handle must be destroyed with [`tpc_destroy`].
+#[no_mangle]
+pub extern "C" fn tpc_create(len: usize) -> *mut TpcChannel {
+    let (channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+        DirectedChannel::create_equal(vec![0; len]);
+    // The master key never leaves the handle, so it only gates the pointers of this channel.
+    let mut master_key = unsafe { MasterKey::create_unlimited() };
+    let (read_only, writable) = {
+        let data_key = master_key.get_data_key();
+        (
+            read_only_data_pointer.get(&data_key).as_ptr(),
+            writable_data_pointer.get_mut(&data_key).as_mut_ptr(),
+        )
+    };
+    Box::into_raw(Box::new(TpcChannel {
+        master_key,
+        channel_pointer,
+        read_only_data_pointer,
+        writable_data_pointer,
+        len,
+        read_only,
+        writable,
+    }))
+}
+
+/// The length in bytes of each buffer of the channel, or zero if the handle is null.
+///
+/// # Safety
+///
+/// The handle must be null or created by [`tpc_create`] and not yet destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn tpc_len(handle: *const TpcChannel) -> usize {
+    handle.as_ref().map_or(0, |handle| handle.len)
+}
+
+/// A pointer to the read-only buffer of the channel, or null if the handle is null.
+/// The pointer stays valid until the handle is destroyed, but must not be accessed during [`tpc_flush`].
+///
+/// # Safety
+///
+/// The handle must be null or created by [`tpc_create`] and not yet destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn tpc_readonly_ptr(handle: *const TpcChannel) -> *const u8 {
+    handle
+        .as_ref()
+        .map_or(ptr::null(), |handle| handle.read_only)
+}
+
+/// A pointer to the writable buffer of the channel, or null if the handle is null.
+/// The pointer stays valid until the handle is destroyed, but must not be accessed during [`tpc_flush`].
+///
+/// # Safety
+///
+/// The handle must be null or created by [`tpc_create`] and not yet destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn tpc_writable_ptr(handle: *const TpcChannel) -> *mut u8 {
+    handle
+        .as_ref()
+        .map_or(ptr::null_mut(), |handle| handle.writable)
+}
+
+/// Copy the writable buffer into the read-only buffer.
+/// The bytes are copied into the existing read-only buffer, so the pointers to the buffers stay valid.
+/// Does nothing if the handle is null.
+///
+/// # Safety
+///
+/// The handle must be null or created by [`tpc_create`] and not yet destroyed.
+/// No other thread may access the handle or the buffers of the channel during the flush.
+#[no_mangle]
+pub unsafe extern "C" fn tpc_flush(handle: *mut TpcChannel) {
+    if let Some(handle) = handle.as_mut() {
+        handle.channel_pointer.flush_with(
+            &handle.master_key.get_channel_key(),
+            |read_only, writable| read_only.copy_from_slice(writable),
+        );
+    }
+}
+
+/// Destroy the channel and free its buffers.
+/// Does nothing if the handle is null.
+///
+/// # Safety
+///
+/// The handle must be null or created by [`tpc_create`] and not yet destroyed.
+/// No other thread may access the handle during the destruction,
+/// and the pointers to the buffers of the channel must not be used anymore.
+#[no_mangle]
+pub unsafe extern "C" fn tpc_destroy(handle: *mut TpcChannel) {
+    if !handle.is_null() {
+        let TpcChannel {
+            master_key,
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+            ..
+        } = *Box::from_raw(handle);
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        drop(master_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ptr, slice};
+
+    use crate::ffi::{
+        tpc_create, tpc_destroy, tpc_flush, tpc_len, tpc_readonly_ptr, tpc_writable_ptr,
+    };
+
+    #[test]
+    fn test_ffi() {
+        unsafe {
+            let handle = tpc_create(4);
+            assert_eq!(tpc_len(handle), 4);
+
+            let writable = tpc_writable_ptr(handle);
+            slice::from_raw_parts_mut(writable, 4).copy_from_slice(&[1, 2, 3, 4]);
+            let read_only = tpc_readonly_ptr(handle);
+            assert_eq!(slice::from_raw_parts(read_only, 4), &[0, 0, 0, 0]);
+
+            tpc_flush(handle);
+            assert_eq!(tpc_readonly_ptr(handle), read_only);
+            assert_eq!(tpc_writable_ptr(handle), writable);
+            assert_eq!(slice::from_raw_parts(read_only, 4), &[1, 2, 3, 4]);
+            tpc_destroy(handle);
+        }
+    }
+
+    #[test]
+    fn test_null_handle() {
+        unsafe {
+            assert_eq!(tpc_len(ptr::null()), 0);
+            assert!(tpc_readonly_ptr(ptr::null()).is_null());
+            assert!(tpc_writable_ptr(ptr::null()).is_null());
+            tpc_flush(ptr::null_mut());
+            tpc_destroy(ptr::null_mut());
+        }
+    }
+}
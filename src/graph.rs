@@ -0,0 +1,369 @@
+//! A dataflow graph of directed channels that are flushed in topological order.
+//! If a stage reads what another stage published in the same flush phase,
+//! flushing the stages in the wrong order would delay the propagation by one phase.
+//!
+//! A stage that computes its `Data` from an upstream channel is added via [`ChannelGraphBuilder::add_stage`].
+//! The graph hands it the upstream read-only `Data` during [`ChannelGraph::flush_in_order`],
+//! so it does not need a [`DataKey`](crate::DataKey) during the flush phase.
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+use crate::{
+    directed::{DirectedChannelPointer, IDirectedChannel},
+    manager::{take_channel, ManagedChannel},
+    ChannelKey,
+};
+
+/// Flushes the channel of a stage, given the upstream channel.
+type StageFlush =
+    Box<dyn FnMut(&dyn ManagedChannel, &mut dyn ManagedChannel, &ChannelKey) + Send + Sync>;
+
+/// A channel added via [`ChannelGraphBuilder::add_stage`].
+struct Stage {
+    upstream: usize,
+    flush: StageFlush,
+}
+
+/// The error returned by [`ChannelGraphBuilder::build`] if the dependencies contain a cycle.
+/// It holds the builder, such that the caller can take the channels out of it to destroy them.
+#[derive(Debug)]
+pub struct CycleError {
+    /// The indices of the channels that are part of a cycle or depend on one, in ascending order.
+    pub channels: Vec<usize>,
+    /// The builder passed to `build`.
+    pub builder: ChannelGraphBuilder,
+}
+
+impl Display for CycleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the channel graph contains a cycle through the channels {:?}",
+            self.channels
+        )
+    }
+}
+
+impl Error for CycleError {}
+
+/// A builder for a [`ChannelGraph`].
+///
+/// Channels are added via [`ChannelGraphBuilder::add_channel`],
+/// and the order in which they are flushed is declared via [`ChannelGraphBuilder::depends_on`].
+#[derive(Default)]
+pub struct ChannelGraphBuilder {
+    channels: Vec<Option<Box<dyn ManagedChannel>>>,
+    stages: Vec<Option<Stage>>,
+    dependents: Vec<Vec<usize>>,
+}
+
+/// Owns directed channels of arbitrary types and flushes them in topological order of their dependencies,
+/// such that data propagates through the whole graph in a single flush phase.
+///
+/// The channels must be taken out again via [`ChannelGraph::take`] to destroy them.
+///
+/// See [`ChannelGraphBuilder`] for how to build a graph.
+pub struct ChannelGraph {
+    channels: Vec<Option<Box<dyn ManagedChannel>>>,
+    stages: Vec<Option<Stage>>,
+    order: Vec<usize>,
+}
+
+impl ChannelGraphBuilder {
+    /// Create a builder without any channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the given channel to the graph.
+    /// Returns the index of the channel, which is used to declare dependencies and to take the channel out of the built graph.
+    pub fn add_channel(&mut self, channel: impl IDirectedChannel + 'static) -> usize {
+        self.channels.push(Some(Box::new(channel)));
+        self.stages.push(None);
+        self.dependents.push(Vec::new());
+        self.channels.len() - 1
+    }
+
+    /// Add the given channel as a stage that publishes `transform` applied to the read-only `Data` of the channel with index `upstream`.
+    /// The stage depends on `upstream`, so within one flush phase it sees what `upstream` published in that phase.
+    /// Returns the index of the stage, which can be taken out of the built graph as a `DirectedChannelPointer<Data>`.
+    ///
+    /// A stage is skipped by [`ChannelGraph::flush_in_order`] once its upstream channel was taken out of the graph.
+    ///
+    /// **Panics** if there is no channel with index `upstream`, or if it is not of type `DirectedChannelPointer<Upstream>`.
+    pub fn add_stage<Upstream: 'static, Data: Clone + Send + Sync + 'static>(
+        &mut self,
+        upstream: usize,
+        channel: DirectedChannelPointer<Data>,
+        mut transform: impl FnMut(&Upstream) -> Data + Send + Sync + 'static,
+    ) -> usize {
+        let upstream_channel = self
+            .channels
+            .get(upstream)
+            .and_then(Option::as_ref)
+            .unwrap_or_else(|| panic!("there is no channel with index {}", upstream));
+        assert!(
+            upstream_channel
+                .as_any()
+                .is::<DirectedChannelPointer<Upstream>>(),
+            "the channel with index {} has a different type",
+            upstream
+        );
+
+        let index = self.add_channel(channel);
+        self.depends_on(index, upstream);
+        self.stages[index] = Some(Stage {
+            upstream,
+            flush: Box::new(move |upstream, channel, channel_key| {
+                // The types were checked above, and the channels of a graph cannot be replaced.
+                let upstream = upstream
+                    .as_any()
+                    .downcast_ref::<DirectedChannelPointer<Upstream>>()
+                    .unwrap();
+                let channel = channel
+                    .as_any_mut()
+                    .downcast_mut::<DirectedChannelPointer<Data>>()
+                    .unwrap();
+                let value = transform(upstream.read_only(channel_key));
+                channel.flush_transform(channel_key, |_| value);
+            }),
+        });
+        index
+    }
+
+    /// Declare that the channel with index `channel` reads what the channel with index `dependency` publishes,
+    /// such that `dependency` is flushed before `channel`.
+    ///
+    /// **Panics** if there is no channel with one of the given indices.
+    pub fn depends_on(&mut self, channel: usize, dependency: usize) -> &mut Self {
+        for index in [channel, dependency] {
+            assert!(
+                index < self.channels.len(),
+                "there is no channel with index {}",
+                index
+            );
+        }
+        self.dependents[dependency].push(channel);
+        self
+    }
+
+    /// Build the graph, computing the order in which the channels are flushed.
+    ///
+    /// Returns an error if the dependencies contain a cycle.
+    pub fn build(self) -> Result<ChannelGraph, CycleError> {
+        let len = self.channels.len();
+        let mut dependency_counts = vec![0; len];
+        for dependent in self.dependents.iter().flatten() {
+            dependency_counts[*dependent] += 1;
+        }
+
+        let mut ready: VecDeque<_> = (0..len)
+            .filter(|index| dependency_counts[*index] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(len);
+        while let Some(index) = ready.pop_front() {
+            order.push(index);
+            for dependent in &self.dependents[index] {
+                dependency_counts[*dependent] -= 1;
+                if dependency_counts[*dependent] == 0 {
+                    ready.push_back(*dependent);
+                }
+            }
+        }
+
+        if order.len() < len {
+            return Err(CycleError {
+                channels: (0..len)
+                    .filter(|index| dependency_counts[*index] > 0)
+                    .collect(),
+                builder: self,
+            });
+        }
+
+        Ok(ChannelGraph {
+            channels: self.channels,
+            stages: self.stages,
+            order,
+        })
+    }
+
+    /// Take the channel with the given index out of this builder, e.g. to destroy it after [`ChannelGraphBuilder::build`] failed.
+    ///
+    /// **Panics** if there is no channel with the given index, or if it is not of type `Channel`.
+    pub fn take<Channel: IDirectedChannel + 'static>(&mut self, index: usize) -> Channel {
+        let channel = take_channel(&mut self.channels, index);
+        self.stages[index] = None;
+        channel
+    }
+}
+
+impl ChannelGraph {
+    /// Create a builder for a graph.
+    pub fn builder() -> ChannelGraphBuilder {
+        ChannelGraphBuilder::new()
+    }
+
+    /// Flush all channels in the graph, each after all the channels it depends on.
+    pub fn flush_in_order(&mut self, channel_key: &ChannelKey) {
+        for index in &self.order {
+            let index = *index;
+            match &mut self.stages[index] {
+                Some(stage) => {
+                    // The upstream channel was added before the stage, so it has a lower index.
+                    let (head, tail) = self.channels.split_at_mut(index);
+                    let (channel, upstream) = (&mut tail[0], &head[stage.upstream]);
+                    if let (Some(channel), Some(upstream)) = (channel, upstream) {
+                        (stage.flush)(&**upstream, &mut **channel, channel_key);
+                    }
+                }
+                None => {
+                    if let Some(channel) = &mut self.channels[index] {
+                        channel.flush(channel_key);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The indices of the channels in the order in which they are flushed.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// The number of channels in the graph that were not taken out yet.
+    pub fn len(&self) -> usize {
+        self.channels
+            .iter()
+            .filter(|channel| channel.is_some())
+            .count()
+    }
+
+    /// Returns `true` if all channels were taken out of the graph.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Take the channel with the given index out of this graph, e.g. to destroy it.
+    /// It is skipped by later flushes.
+    ///
+    /// **Panics** if there is no channel with the given index, or if it is not of type `Channel`.
+    pub fn take<Channel: IDirectedChannel + 'static>(&mut self, index: usize) -> Channel {
+        let channel = take_channel(&mut self.channels, index);
+        self.stages[index] = None;
+        channel
+    }
+}
+
+impl Debug for ChannelGraphBuilder {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ChannelGraphBuilder")
+            .field(
+                "len",
+                &self
+                    .channels
+                    .iter()
+                    .filter(|channel| channel.is_some())
+                    .count(),
+            )
+            .field("dependents", &self.dependents)
+            .finish()
+    }
+}
+
+impl Debug for ChannelGraph {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ChannelGraph")
+            .field("len", &self.len())
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        directed::{DirectedChannel, DirectedChannelPointer},
+        graph::{ChannelGraph, CycleError},
+        MasterKey,
+    };
+
+    #[test]
+    fn test_chain_propagates_in_one_flush() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (input, input_reader, mut input_writer) = DirectedChannel::create(0, 0);
+        let (stage1, stage1_reader, stage1_writer) = DirectedChannel::create(0, 0);
+        let (stage2, output_reader, stage2_writer) =
+            DirectedChannel::create(String::new(), String::new());
+
+        let mut builder = ChannelGraph::builder();
+        let input = builder.add_channel(input);
+        let stage1 = builder.add_stage(input, stage1, |value: &i32| value + 1);
+        let stage2 = builder.add_stage(stage1, stage2, |value: &i32| value.to_string());
+        let mut graph = builder.build().unwrap();
+        assert_eq!(graph.order(), [input, stage1, stage2]);
+
+        *input_writer.get_mut(&master_key.get_data_key()) = 10;
+        graph.flush_in_order(&master_key.get_channel_key());
+        assert_eq!(output_reader.get(&master_key.get_data_key()), "11");
+
+        // Once its upstream channel is taken out, a stage is skipped.
+        graph
+            .take::<DirectedChannelPointer<i32>>(input)
+            .destroy_single(input_reader, input_writer);
+        graph.flush_in_order(&master_key.get_channel_key());
+        assert_eq!(output_reader.get(&master_key.get_data_key()), "11");
+
+        graph
+            .take::<DirectedChannelPointer<String>>(stage2)
+            .destroy_single(output_reader, stage2_writer);
+        graph
+            .take::<DirectedChannelPointer<i32>>(stage1)
+            .destroy_single(stage1_reader, stage1_writer);
+        assert!(graph.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "has a different type")]
+    fn test_add_stage_wrong_upstream_type() {
+        let (upstream, _upstream_reader, _upstream_writer) = DirectedChannel::create(0u8, 0);
+        let (channel, _read_only_data_pointer, _writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let mut builder = ChannelGraph::builder();
+        let upstream = builder.add_channel(upstream);
+        builder.add_stage(upstream, channel, |value: &i32| *value);
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut builder = ChannelGraph::builder();
+        let pointers: Vec<_> = (0..3)
+            .map(|_| {
+                let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+                    DirectedChannel::create(0, 0);
+                (
+                    builder.add_channel(channel_pointer),
+                    read_only_data_pointer,
+                    writable_data_pointer,
+                )
+            })
+            .collect();
+        builder.depends_on(1, 0).depends_on(2, 1).depends_on(1, 2);
+
+        let CycleError {
+            channels,
+            mut builder,
+        } = builder.build().unwrap_err();
+        assert_eq!(channels, [1, 2]);
+        for (index, read_only_data_pointer, writable_data_pointer) in pointers {
+            builder
+                .take::<DirectedChannelPointer<i32>>(index)
+                .destroy_single(read_only_data_pointer, writable_data_pointer);
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Guards that borrow a key for a whole phase,
+//! so that the key does not need to be passed to every single access.
+
+use std::{
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+use crate::{
+    directed::{
+        DirectedChannelPointer, IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer,
+    },
+    ChannelKey, DataKey,
+};
+
+/// A guard for reading data pointers, obtained via [`DataKey::read_guard`].
+///
+/// The guard borrows the data key, so the data key cannot be converted into a channel key while the guard exists.
+pub struct ReadGuard<'key> {
+    data_key: &'key DataKey<'key>,
+}
+
+/// A guard for flushing channels, obtained via [`ChannelKey::flush_guard`].
+///
+/// The guard borrows the channel key, so the channel key cannot be converted into a data key while the guard exists.
+pub struct FlushGuard<'key> {
+    channel_key: &'key ChannelKey<'key>,
+}
+
+/// A channel key that can be shared between threads, which take turns using it.
+/// Only one thread can use the channel key at any time, so the flush phase can move between threads,
+/// e.g. if several threads take turns flushing the same channels.
+pub struct SyncChannelKey<'key> {
+    channel_key: Mutex<ChannelKey<'key>>,
+}
+
+/// A guard for building up the writable `Data` of a directed channel, which flushes the channel when it is dropped,
+/// obtained via [`WritableDataPointer::flush_on_drop`].
+///
+/// The guard borrows the channel key for its whole lifetime, so no data key can exist while the writable `Data` is built up.
+/// This is sound because only the writable data pointer and the channel pointer access the writable `Data`, and the guard borrows both.
+/// Use [`FlushOnDrop::abort`] to drop the guard without flushing.
+/// The guard does not flush either if it is dropped while the thread is panicking,
+/// since the writable `Data` may have been left half-built.
+pub struct FlushOnDrop<'a, Data: Clone> {
+    channel_pointer: &'a mut DirectedChannelPointer<Data>,
+    writable_data_pointer: &'a mut WritableDataPointer<Data>,
+    channel_key: &'a ChannelKey<'a>,
+    aborted: bool,
+}
+
+/// A guard for modifying the writable `Data` of a directed channel tentatively, obtained via [`WritableDataPointer::transaction`].
+///
+/// The transaction snapshots the writable `Data` when it is created.
+/// [`Transaction::commit`] keeps the changes, while [`Transaction::rollback`] or dropping the transaction restores the snapshot.
+pub struct Transaction<'a, Data: Clone> {
+    writable_data_pointer: &'a mut WritableDataPointer<Data>,
+    data_key: &'a DataKey<'a>,
+    snapshot: Option<Data>,
+}
+
+impl<'key> DataKey<'key> {
+    /// Get a guard for reading data pointers without passing the data key to each access.
+    pub fn read_guard(&self) -> ReadGuard<'_> {
+        ReadGuard { data_key: self }
+    }
+}
+
+impl<'key> ReadGuard<'key> {
+    /// Get a reference to the `Data` field pointed to by the given pointer.
+    ///
+    /// See [`ReadOnlyDataPointer::get`].
+    pub fn read<'a, Data>(&'a self, pointer: &'a ReadOnlyDataPointer<Data>) -> &'a Data {
+        pointer.get(self.data_key)
+    }
+
+    /// Get a reference to the `Data` field pointed to by the given writable pointer.
+    ///
+    /// See [`WritableDataPointer::get`].
+    pub fn read_writable<'a, Data>(&'a self, pointer: &'a WritableDataPointer<Data>) -> &'a Data {
+        pointer.get(self.data_key)
+    }
+}
+
+impl<'key> ChannelKey<'key> {
+    /// Get a guard for flushing channels without passing the channel key to each flush.
+    pub fn flush_guard(&self) -> FlushGuard<'_> {
+        FlushGuard { channel_key: self }
+    }
+}
+
+impl<'key> FlushGuard<'key> {
+    /// Flush the given channel.
+    ///
+    /// See [`IDirectedChannel::flush`].
+    pub fn flush(&self, channel: &mut impl IDirectedChannel) {
+        channel.flush(self.channel_key);
+    }
+
+    /// Flush all given channels in order.
+    pub fn flush_all(&self, channels: &mut [&mut dyn IDirectedChannel]) {
+        for channel in channels {
+            channel.flush(self.channel_key);
+        }
+    }
+}
+
+impl<Data: Clone> WritableDataPointer<Data> {
+    /// Get a guard for modifying the `Data` field pointed to by this pointer, which flushes the given channel when it is dropped.
+    ///
+    /// **Panics** if this pointer does not point to the given channel.
+    pub fn flush_on_drop<'a>(
+        &'a mut self,
+        channel_pointer: &'a mut DirectedChannelPointer<Data>,
+        channel_key: &'a ChannelKey,
+    ) -> FlushOnDrop<'a, Data> {
+        assert!(
+            channel_pointer.owns_writable(self),
+            "the writable data pointer must point to the given channel"
+        );
+        FlushOnDrop {
+            channel_pointer,
+            writable_data_pointer: self,
+            channel_key,
+            aborted: false,
+        }
+    }
+}
+
+impl<Data: Clone> WritableDataPointer<Data> {
+    /// Start a transaction that modifies the `Data` field pointed to by this pointer tentatively.
+    /// This clones the `Data` as snapshot for a rollback.
+    pub fn transaction<'a>(&'a mut self, data_key: &'a DataKey) -> Transaction<'a, Data> {
+        let snapshot = self.get(data_key).clone();
+        Transaction {
+            writable_data_pointer: self,
+            data_key,
+            snapshot: Some(snapshot),
+        }
+    }
+}
+
+impl<'a, Data: Clone> Transaction<'a, Data> {
+    /// Get a reference to the writable `Data`.
+    pub fn get(&self) -> &Data {
+        self.writable_data_pointer.get(self.data_key)
+    }
+
+    /// Get a mutable reference to the writable `Data`.
+    ///
+    /// See [`WritableDataPointer::get_mut`].
+    pub fn get_mut(&mut self) -> &mut Data {
+        self.writable_data_pointer.get_mut(self.data_key)
+    }
+
+    /// Keep the changes made in this transaction.
+    pub fn commit(mut self) {
+        self.snapshot = None;
+    }
+
+    /// Discard the changes made in this transaction by restoring the snapshot.
+    /// This is the same as dropping the transaction.
+    pub fn rollback(self) {}
+}
+
+impl<'a, Data: Clone> Drop for Transaction<'a, Data> {
+    fn drop(&mut self) {
+        if let Some(snapshot) = self.snapshot.take() {
+            *self.writable_data_pointer.get_mut(self.data_key) = snapshot;
+        }
+    }
+}
+
+impl<'a, Data: Clone> FlushOnDrop<'a, Data> {
+    /// Get a reference to the writable `Data`.
+    pub fn get(&self) -> &Data {
+        self.writable_data_pointer.get(&DataKey {
+            scope: Default::default(),
+        })
+    }
+
+    /// Get a mutable reference to the writable `Data`.
+    ///
+    /// See [`WritableDataPointer::get_mut`].
+    pub fn get_mut(&mut self) -> &mut Data {
+        self.writable_data_pointer.get_mut(&DataKey {
+            scope: Default::default(),
+        })
+    }
+
+    /// Drop this guard without flushing the channel.
+    /// The changes to the writable `Data` are kept, and are flushed by the next flush of the channel.
+    pub fn abort(mut self) {
+        self.aborted = true;
+    }
+}
+
+impl<'a, Data: Clone> Drop for FlushOnDrop<'a, Data> {
+    fn drop(&mut self) {
+        if !self.aborted && !thread::panicking() {
+            self.channel_pointer.flush(self.channel_key);
+        }
+    }
+}
+
+impl<'key> SyncChannelKey<'key> {
+    /// Wrap the given channel key such that it can be shared between threads.
+    pub fn new(channel_key: ChannelKey<'key>) -> Self {
+        Self {
+            channel_key: Mutex::new(channel_key),
+        }
+    }
+
+    /// Run the given closure with the channel key.
+    /// Blocks while another thread uses the channel key.
+    pub fn with<R>(&self, f: impl FnOnce(&ChannelKey) -> R) -> R {
+        // The key has no state, so it stays valid if a closure panicked.
+        let channel_key = self
+            .channel_key
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        f(&channel_key)
+    }
+
+    /// Unwrap the channel key, e.g. to convert it into a data key.
+    pub fn into_inner(self) -> ChannelKey<'key> {
+        self.channel_key
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::Mutex,
+        thread,
+    };
+
+    use crate::{
+        directed::{DirectedChannel, IDirectedChannel},
+        guard::SyncChannelKey,
+        MasterKey,
+    };
+
+    #[test]
+    fn test_read_guard() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create(1, 0);
+        let (channel_pointer2, read_only_data_pointer2, writable_data_pointer2) =
+            DirectedChannel::create("two", "");
+        let (channel_pointer3, read_only_data_pointer3, writable_data_pointer3) =
+            DirectedChannel::create(vec![3], vec![]);
+
+        let data_key = master_key.get_data_key();
+        let guard = data_key.read_guard();
+        assert_eq!(*guard.read(&read_only_data_pointer1), 1);
+        assert_eq!(*guard.read(&read_only_data_pointer2), "two");
+        assert_eq!(*guard.read(&read_only_data_pointer3), [3]);
+        assert_eq!(*guard.read_writable(&writable_data_pointer1), 0);
+
+        DirectedChannel::destroy_single(
+            channel_pointer1,
+            read_only_data_pointer1,
+            writable_data_pointer1,
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer2,
+            read_only_data_pointer2,
+            writable_data_pointer2,
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer3,
+            read_only_data_pointer3,
+            writable_data_pointer3,
+        );
+    }
+
+    #[test]
+    fn test_flush_guard() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create(0, 1);
+        let (mut channel_pointer2, read_only_data_pointer2, writable_data_pointer2) =
+            DirectedChannel::create("", "two");
+        let (mut channel_pointer3, read_only_data_pointer3, writable_data_pointer3) =
+            DirectedChannel::create(vec![], vec![3]);
+
+        let channel_key = master_key.get_channel_key();
+        {
+            let guard = channel_key.flush_guard();
+            guard.flush(&mut channel_pointer1);
+            guard.flush_all(&mut [
+                &mut channel_pointer2 as &mut dyn IDirectedChannel,
+                &mut channel_pointer3,
+            ]);
+        }
+
+        let data_key = channel_key.into_data_key();
+        assert_eq!(*read_only_data_pointer1.get(&data_key), 1);
+        assert_eq!(*read_only_data_pointer2.get(&data_key), "two");
+        assert_eq!(*read_only_data_pointer3.get(&data_key), [3]);
+
+        DirectedChannel::destroy_single(
+            channel_pointer1,
+            read_only_data_pointer1,
+            writable_data_pointer1,
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer2,
+            read_only_data_pointer2,
+            writable_data_pointer2,
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer3,
+            read_only_data_pointer3,
+            writable_data_pointer3,
+        );
+    }
+
+    #[test]
+    fn test_sync_channel_key() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 1);
+        let channel_pointer = Mutex::new(channel_pointer);
+
+        let channel_key = SyncChannelKey::new(master_key.get_channel_key());
+        thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    channel_key
+                        .with(|channel_key| channel_pointer.lock().unwrap().flush(channel_key));
+                });
+            }
+        });
+
+        let data_key = channel_key.into_inner().into_data_key();
+        let channel_pointer = channel_pointer.into_inner().unwrap();
+        assert_eq!(channel_pointer.flush_count(), 2);
+        assert_eq!(*read_only_data_pointer.get(&data_key), 1);
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_flush_on_drop() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![], vec![]);
+
+        {
+            let channel_key = master_key.get_channel_key();
+            let mut guard = writable_data_pointer.flush_on_drop(&mut channel_pointer, &channel_key);
+            guard.get_mut().push(1);
+            guard.get_mut().push(2);
+            assert_eq!(*guard.get(), [1, 2]);
+        }
+        assert_eq!(channel_pointer.flush_count(), 1);
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [1, 2]
+        );
+
+        {
+            let channel_key = master_key.get_channel_key();
+            let mut guard = writable_data_pointer.flush_on_drop(&mut channel_pointer, &channel_key);
+            guard.get_mut().push(3);
+            guard.abort();
+        }
+        assert_eq!(channel_pointer.flush_count(), 1);
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [1, 2]
+        );
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_flush_on_drop_panicking() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![], vec![]);
+
+        {
+            let channel_key = master_key.get_channel_key();
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut guard =
+                    writable_data_pointer.flush_on_drop(&mut channel_pointer, &channel_key);
+                guard.get_mut().push(1);
+                panic!("building the data failed");
+            }));
+            assert!(result.is_err());
+        }
+        assert_eq!(channel_pointer.flush_count(), 0);
+        assert!(read_only_data_pointer
+            .get(&master_key.get_data_key())
+            .is_empty());
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(vec![], vec![1]);
+
+        {
+            let data_key = master_key.get_data_key();
+            let mut transaction = writable_data_pointer.transaction(&data_key);
+            transaction.get_mut().push(2);
+            assert_eq!(*transaction.get(), [1, 2]);
+            transaction.rollback();
+            assert_eq!(*writable_data_pointer.get(&data_key), [1]);
+
+            writable_data_pointer
+                .transaction(&data_key)
+                .get_mut()
+                .clear();
+            assert_eq!(*writable_data_pointer.get(&data_key), [1]);
+
+            let mut transaction = writable_data_pointer.transaction(&data_key);
+            transaction.get_mut().push(3);
+            transaction.commit();
+            assert_eq!(*writable_data_pointer.get(&data_key), [1, 3]);
+        }
+
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [1, 3]
+        );
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! A single owner of a directed two-phase channel and its pointers,
+//! for the common case where one thread reads, writes and flushes the channel.
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey,
+};
+
+/// Owns the channel pointer of a directed channel together with its read-only and its writable data pointer.
+///
+/// This type should always be unwrapped via [DirectedChannelHandle::into_inner] or destroyed via [DirectedChannelHandle::destroy]
+/// to ensure soundness (at runtime), just like a [DirectedChannelPointer].
+#[derive(Debug)]
+#[must_use]
+pub struct DirectedChannelHandle<Data> {
+    channel_pointer: DirectedChannelPointer<Data>,
+    read_only_data_pointer: ReadOnlyDataPointer<Data>,
+    writable_data_pointer: WritableDataPointer<Data>,
+}
+
+impl<Data> DirectedChannelHandle<Data> {
+    /// Create a directed channel and bundle its pointers into a handle.
+    ///
+    /// See [DirectedChannel::create] for more info.
+    pub fn create(read_only: Data, writable: Data) -> Self {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(read_only, writable);
+        Self {
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        }
+    }
+
+    /// Bundle the given pointers into a handle.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn from_pointers(
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointer: ReadOnlyDataPointer<Data>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> Self {
+        assert!(channel_pointer.owns_read_only(&read_only_data_pointer));
+        assert!(channel_pointer.owns_writable(&writable_data_pointer));
+        Self {
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        }
+    }
+
+    /// Read the read-only `Data` of the channel.
+    ///
+    /// Shorthand for [ReadOnlyDataPointer::get].
+    pub fn read(&self, data_key: &DataKey) -> &Data {
+        self.read_only_data_pointer.get(data_key)
+    }
+
+    /// Apply `f` to the writable `Data` of the channel.
+    ///
+    /// Shorthand for [WritableDataPointer::get_mut].
+    pub fn write<Output>(
+        &mut self,
+        data_key: &DataKey,
+        f: impl FnOnce(&mut Data) -> Output,
+    ) -> Output {
+        f(self.writable_data_pointer.get_mut(data_key))
+    }
+
+    /// Unwrap the handle into the channel pointer, the read-only data pointer and the writable data pointer.
+    pub fn into_inner(
+        self,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        (
+            self.channel_pointer,
+            self.read_only_data_pointer,
+            self.writable_data_pointer,
+        )
+    }
+
+    /// Destroys the channel of this handle.
+    /// Returns the read-only and the writable `Data`.
+    ///
+    /// See [DirectedChannel::destroy_single].
+    pub fn destroy(self) -> (Data, Data) {
+        self.channel_pointer
+            .destroy_single(self.read_only_data_pointer, self.writable_data_pointer)
+    }
+}
+
+impl<Data: Clone> DirectedChannelHandle<Data> {
+    /// Shorthand for [DirectedChannelPointer::flush].
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        self.channel_pointer.flush(channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{handle::DirectedChannelHandle, MasterKey};
+
+    #[test]
+    fn test_read_write_flush_loop() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut handle = DirectedChannelHandle::create(Vec::new(), Vec::new());
+
+        for i in 0..10 {
+            let data_key = master_key.get_data_key();
+            assert_eq!(handle.read(&data_key).len(), i);
+            handle.write(&data_key, |data| data.push(i));
+            assert_eq!(handle.read(&data_key).len(), i);
+            handle.flush(&master_key.get_channel_key());
+        }
+
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) = handle.into_inner();
+        assert_eq!(channel_pointer.flush_count(), 10);
+        let (read_only, writable) =
+            channel_pointer.destroy_single(read_only_data_pointer, writable_data_pointer);
+        assert_eq!(read_only, (0..10).collect::<Vec<_>>());
+        assert_eq!(writable, read_only);
+    }
+}
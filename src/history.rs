@@ -0,0 +1,243 @@
+//! A history two-phase channel.
+//! This is a wrapper around a directed two-phase channel,
+//! which keeps the last published values for inspection by its readers.
+
+use std::{collections::VecDeque, ptr};
+
+use crate::{
+    directed::{DirectedChannel, IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey,
+};
+
+/// A history channel used for communication between threads.
+/// It holds a directed channel and a ring buffer of the read-only `Data` of the last flushes,
+/// e.g. for the post-mortem inspection of the last frames of a simulation.
+///
+/// See [`DirectedChannel`](crate::directed::DirectedChannel) for more info.
+#[derive(Debug)]
+pub struct HistoryChannel<Data> {
+    channel: DirectedChannel<Data>,
+    history: VecDeque<Data>,
+    capacity: usize,
+}
+
+/// A pointer to a history channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [HistoryChannel::destroy] or [HistoryChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct HistoryChannelPointer<Data> {
+    channel: Box<HistoryChannel<Data>>,
+}
+
+/// A pointer to the read-only data field and the history in a history channel.
+/// It can only be accessed using a [DataKey].
+///
+/// This type should always be destroyed via the [HistoryChannel::destroy] or [HistoryChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct HistoryDataPointer<Data> {
+    data: ReadOnlyDataPointer<Data>,
+    history: *const VecDeque<Data>,
+}
+
+impl<Data> HistoryChannel<Data> {
+    /// Create a history channel that remembers the read-only `Data` of the last `capacity` flushes, and hand out three pointers to it.
+    /// One [HistoryChannelPointer] used to flush (copy) the content of the writable `Data` field into the read-only data field,
+    /// one [HistoryDataPointer] used to read from the channel, and
+    /// one [WritableDataPointer] used to write to the channel.
+    ///
+    /// The history is initially empty, so it does not contain the initial read-only `Data`.
+    ///
+    /// **Panics** if the capacity is zero.
+    pub fn create(
+        capacity: usize,
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        HistoryChannelPointer<Data>,
+        HistoryDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        assert!(capacity > 0, "the capacity of a history must not be zero");
+        let mut channel_pointer = HistoryChannelPointer {
+            channel: Box::new(HistoryChannel {
+                channel: DirectedChannel::new(read_only, writable),
+                history: VecDeque::with_capacity(capacity),
+                capacity,
+            }),
+        };
+        let channel: &mut HistoryChannel<Data> = &mut channel_pointer.channel;
+        let history_data_pointer = HistoryDataPointer {
+            data: channel.channel.read_only_data_pointer(),
+            history: &channel.history,
+        };
+        let writable_data_pointer = channel.channel.writable_data_pointer();
+        (channel_pointer, history_data_pointer, writable_data_pointer)
+    }
+
+    /// Destroys the history channel linked with the given pointers (see [HistoryChannel::create]).
+    /// Returns the read-only and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: HistoryChannelPointer<Data>,
+        history_data_pointers: impl IntoIterator<Item = HistoryDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        let HistoryChannelPointer { channel } = channel_pointer;
+        let WritableDataPointer { data: writable, .. } = writable_data_pointer;
+        assert!(ptr::eq(&channel.channel.writable.0, writable.as_ptr()));
+        for history_data_pointer in history_data_pointers {
+            let HistoryDataPointer {
+                data:
+                    ReadOnlyDataPointer {
+                        data: read_only, ..
+                    },
+                ..
+            } = history_data_pointer;
+            assert!(ptr::eq(&channel.channel.read_only.0, read_only.as_ptr()));
+        }
+
+        let HistoryChannel { channel, .. } = *channel;
+        (channel.read_only.0, channel.writable.0)
+    }
+}
+
+impl<Data: Clone> HistoryChannelPointer<Data> {
+    /// Clone the writable `Data` into the read-only `Data`, and push it into the history.
+    /// If the history is full, its oldest entry is evicted.
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        let channel: &mut HistoryChannel<Data> = &mut self.channel;
+        channel.channel.flush(channel_key);
+        if channel.history.len() == channel.capacity {
+            channel.history.pop_front();
+        }
+        channel
+            .history
+            .push_back(channel.channel.read_only.0.clone());
+        // Readers access the history as a single slice.
+        channel.history.make_contiguous();
+    }
+
+    /// Restore the read-only `Data` to the previous entry of the history, and remove the current entry from the history,
+    /// e.g. if the current read-only `Data` turned out to be bad.
+    /// Returns `false` if there is no previous entry, in which case nothing changes.
+    ///
+    /// This counts as a flush, since the read-only `Data` changes.
+    pub fn rollback(&mut self, #[allow(unused)] channel_key: &ChannelKey) -> bool {
+        let channel: &mut HistoryChannel<Data> = &mut self.channel;
+        if channel.history.len() < 2 {
+            return false;
+        }
+        channel.history.pop_back();
+        if let Some(previous) = channel.history.back() {
+            channel.channel.read_only.0 = previous.clone();
+        }
+        channel.channel.flushed_partially();
+        true
+    }
+}
+
+impl<Data> HistoryChannelPointer<Data> {
+    /// The maximum number of entries in the history.
+    pub fn capacity(&self) -> usize {
+        self.channel.capacity
+    }
+
+    /// Shorthand for [HistoryChannel::destroy].
+    pub fn destroy(
+        self,
+        history_data_pointers: impl IntoIterator<Item = HistoryDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        HistoryChannel::destroy(self, history_data_pointers, writable_data_pointer)
+    }
+}
+
+impl<Data> HistoryDataPointer<Data> {
+    /// Get a reference to the read-only `Data` field pointed to by this pointer.
+    pub fn get(&self, data_key: &DataKey) -> &Data {
+        self.data.get(data_key)
+    }
+
+    /// Get the read-only `Data` of the last flushes, from the oldest to the newest.
+    /// The newest entry is the current read-only `Data`.
+    pub fn history(&self, #[allow(unused)] data_key: &DataKey) -> &[Data] {
+        unsafe { (*self.history).as_slices().0 }
+    }
+}
+
+impl<Data> Clone for HistoryDataPointer<Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Data> Copy for HistoryDataPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Send for HistoryChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for HistoryDataPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for HistoryChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for HistoryDataPointer<Data> {}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for HistoryChannelPointer<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        HistoryChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{history::HistoryChannel, MasterKey};
+
+    #[test]
+    fn test_retained_window() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, history_data_pointer, mut writable_data_pointer) =
+            HistoryChannel::create(64, 0, 0);
+        assert!(history_data_pointer
+            .history(&master_key.get_data_key())
+            .is_empty());
+
+        for i in 1..=100 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = i;
+            channel_pointer.flush(&master_key.get_channel_key());
+        }
+
+        let data_key = master_key.get_data_key();
+        let history = history_data_pointer.history(&data_key);
+        assert_eq!(history, (37..=100).collect::<Vec<_>>());
+        assert_eq!(history.last(), Some(history_data_pointer.get(&data_key)));
+
+        HistoryChannel::destroy(
+            channel_pointer,
+            [history_data_pointer],
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, history_data_pointer, mut writable_data_pointer) =
+            HistoryChannel::create(8, "", "");
+
+        for value in ["A", "B"] {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = value;
+            channel_pointer.flush(&master_key.get_channel_key());
+        }
+        assert_eq!(*history_data_pointer.get(&master_key.get_data_key()), "B");
+
+        assert!(channel_pointer.rollback(&master_key.get_channel_key()));
+        let data_key = master_key.get_data_key();
+        assert_eq!(*history_data_pointer.get(&data_key), "A");
+        assert_eq!(history_data_pointer.history(&data_key), ["A"]);
+        assert!(!channel_pointer.rollback(&master_key.get_channel_key()));
+        assert_eq!(*history_data_pointer.get(&master_key.get_data_key()), "A");
+
+        channel_pointer.destroy([history_data_pointer], writable_data_pointer);
+    }
+}
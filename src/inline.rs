@@ -0,0 +1,208 @@
+//! A directed two-phase channel that is stored inline instead of on the heap.
+//! Its pointers borrow the channel, so the borrow checker ensures that they do not outlive it,
+//! and the channel does not need to be destroyed.
+
+use std::{marker::PhantomData, ptr::NonNull};
+
+use crate::{ChannelKey, DataKey};
+
+/// A directed channel that stores its two instances of `Data` inline, e.g. on the stack of a thread.
+/// Like a [`DirectedChannel`](crate::directed::DirectedChannel), it is accessed via a channel pointer and two data pointers,
+/// but these pointers borrow the channel, so they can only be used within its scope.
+///
+/// See [DirectedChannelInline::split] for more info.
+#[derive(Debug, Clone, Default)]
+pub struct DirectedChannelInline<Data> {
+    read_only: Data,
+    writable: Data,
+}
+
+/// A pointer to an inline directed channel.
+/// It can only be accessed using a [ChannelKey].
+#[derive(Debug)]
+pub struct InlineChannelPointer<'channel, Data> {
+    read_only: NonNull<Data>,
+    writable: NonNull<Data>,
+    scope: PhantomData<&'channel mut DirectedChannelInline<Data>>,
+}
+
+/// A pointer to the read-only data field in an inline directed channel.
+/// It can only be accessed using a [DataKey].
+#[derive(Debug)]
+pub struct InlineReadOnlyDataPointer<'channel, Data> {
+    data: NonNull<Data>,
+    scope: PhantomData<&'channel Data>,
+}
+
+/// A pointer to the writable data field in an inline directed channel.
+/// It can only be accessed using a [DataKey].
+#[derive(Debug)]
+pub struct InlineWritableDataPointer<'channel, Data> {
+    data: NonNull<Data>,
+    scope: PhantomData<&'channel mut Data>,
+}
+
+impl<Data> DirectedChannelInline<Data> {
+    /// Create an inline directed channel with the given read-only and writable `Data`.
+    ///
+    /// This is a `const fn`, so the channel can be initialised at compile time, e.g. in a `static`.
+    pub const fn new(read_only: Data, writable: Data) -> Self {
+        Self {
+            read_only,
+            writable,
+        }
+    }
+
+    /// Hand out three pointers to this channel that borrow it mutably.
+    /// One [InlineChannelPointer] used to transfer the writable `Data` into the read-only `Data`,
+    /// one [InlineReadOnlyDataPointer] used to read the read-only `Data`
+    /// and one [InlineWritableDataPointer] used to read and write the writable `Data`.
+    ///
+    /// Unlike the pointers of a [`DirectedChannel`](crate::directed::DirectedChannel), the pointers can only be used while the channel is borrowed,
+    /// for example from scoped threads via [`std::thread::scope`].
+    pub fn split(
+        &mut self,
+    ) -> (
+        InlineChannelPointer<'_, Data>,
+        InlineReadOnlyDataPointer<'_, Data>,
+        InlineWritableDataPointer<'_, Data>,
+    ) {
+        let read_only = NonNull::from(&mut self.read_only);
+        let writable = NonNull::from(&mut self.writable);
+        (
+            InlineChannelPointer {
+                read_only,
+                writable,
+                scope: PhantomData,
+            },
+            InlineReadOnlyDataPointer {
+                data: read_only,
+                scope: PhantomData,
+            },
+            InlineWritableDataPointer {
+                data: writable,
+                scope: PhantomData,
+            },
+        )
+    }
+
+    /// Get a reference to the read-only `Data`.
+    pub fn read_only(&self) -> &Data {
+        &self.read_only
+    }
+
+    /// Get a reference to the writable `Data`.
+    pub fn writable(&self) -> &Data {
+        &self.writable
+    }
+
+    /// Consume the channel and return the read-only and the writable `Data`.
+    pub fn into_inner(self) -> (Data, Data) {
+        (self.read_only, self.writable)
+    }
+}
+
+impl<Data: Clone> DirectedChannelInline<Data> {
+    /// Create an inline directed channel where both `Data` fields are initialised equally from the given `Data`.
+    pub fn new_equal(data: Data) -> Self {
+        Self::new(data.clone(), data)
+    }
+}
+
+impl<'channel, Data: Clone> InlineChannelPointer<'channel, Data> {
+    /// Clone the writable `Data` into the read-only `Data`.
+    /// The clone is only assigned once it is complete, so if [`Clone::clone`] panics, the read-only `Data` stays unchanged.
+    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        unsafe { *self.read_only.as_ptr() = self.writable.as_ref().clone() };
+    }
+}
+
+impl<'channel, Data> InlineReadOnlyDataPointer<'channel, Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer.
+    pub fn get(&self, #[allow(unused)] data_key: &DataKey) -> &Data {
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<'channel, Data> InlineWritableDataPointer<'channel, Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer.
+    pub fn get(&self, #[allow(unused)] data_key: &DataKey) -> &Data {
+        unsafe { self.data.as_ref() }
+    }
+
+    /// Get a mutable reference to the `Data` field pointed to by this pointer.
+    pub fn get_mut(&mut self, #[allow(unused)] data_key: &DataKey) -> &mut Data {
+        unsafe { self.data.as_mut() }
+    }
+}
+
+impl<'channel, Data> Clone for InlineReadOnlyDataPointer<'channel, Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'channel, Data> Copy for InlineReadOnlyDataPointer<'channel, Data> {}
+
+unsafe impl<'channel, Data: Send + Sync> Send for InlineChannelPointer<'channel, Data> {}
+unsafe impl<'channel, Data: Send + Sync> Send for InlineReadOnlyDataPointer<'channel, Data> {}
+unsafe impl<'channel, Data: Send + Sync> Send for InlineWritableDataPointer<'channel, Data> {}
+
+unsafe impl<'channel, Data: Send + Sync> Sync for InlineChannelPointer<'channel, Data> {}
+unsafe impl<'channel, Data: Send + Sync> Sync for InlineReadOnlyDataPointer<'channel, Data> {}
+unsafe impl<'channel, Data: Send + Sync> Sync for InlineWritableDataPointer<'channel, Data> {}
+
+#[cfg(test)]
+mod tests {
+    use std::{ptr, thread};
+
+    use crate::{inline::DirectedChannelInline, MasterKey};
+
+    #[test]
+    fn test_flush_in_scope() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut channel = DirectedChannelInline::new_equal(0);
+        {
+            let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+                channel.split();
+
+            for i in 1..=3 {
+                let data_key = master_key.get_data_key();
+                thread::scope(|scope| {
+                    scope.spawn(|| {
+                        assert_eq!(*read_only_data_pointer.get(&data_key), i - 1);
+                    });
+                    scope.spawn(|| {
+                        *writable_data_pointer.get_mut(&data_key) = i;
+                    });
+                });
+                channel_pointer.flush(&data_key.into_channel_key());
+            }
+        }
+
+        assert_eq!(*channel.read_only(), 3);
+        assert_eq!(channel.into_inner(), (3, 3));
+    }
+
+    #[test]
+    fn test_static_channel() {
+        static mut CHANNEL: DirectedChannelInline<[u32; 4]> =
+            DirectedChannelInline::new([0; 4], [0; 4]);
+
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        // This test is the only user of the static, so the mutable reference is unique.
+        let channel = unsafe { &mut *ptr::addr_of_mut!(CHANNEL) };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            channel.split();
+        writable_data_pointer.get_mut(&master_key.get_data_key())[1] = 5;
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [0; 4]
+        );
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            *read_only_data_pointer.get(&master_key.get_data_key()),
+            [0, 5, 0, 0]
+        );
+    }
+}
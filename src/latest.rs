@@ -0,0 +1,197 @@
+//! A latest-value two-phase channel.
+//! This is a wrapper around a directed two-phase channel,
+//! whose reader can detect if it already consumed the latest flushed data.
+
+use std::ptr;
+
+use crate::{
+    directed::{DirectedChannel, IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey,
+};
+
+/// A latest-value channel used for communication between threads.
+/// It holds a directed channel and remembers whether its read-only `Data` was already consumed.
+///
+/// See [`DirectedChannel`](crate::directed::DirectedChannel) for more info.
+#[derive(Debug)]
+pub struct LatestChannel<Data> {
+    channel: DirectedChannel<Data>,
+    consumed: bool,
+}
+
+/// A pointer to a latest-value channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [LatestChannel::destroy] or [LatestChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct LatestChannelPointer<Data> {
+    channel: Box<LatestChannel<Data>>,
+}
+
+/// A pointer to the read-only data field in a latest-value channel.
+/// It can only be accessed using a [DataKey].
+///
+/// Unlike a [`ReadOnlyDataPointer`], this pointer cannot be copied, since consuming the `Data` modifies the channel.
+///
+/// This type should always be destroyed via the [LatestChannel::destroy] or [LatestChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct LatestDataPointer<Data> {
+    data: ReadOnlyDataPointer<Data>,
+    consumed: *mut bool,
+}
+
+impl<Data> LatestChannel<Data> {
+    /// Create a latest-value channel and hand out three pointers to it.
+    /// One [LatestChannelPointer] used to flush (copy) the content of the writable `Data` field into the read-only data field,
+    /// one [LatestDataPointer] used to read from the channel, and
+    /// one [WritableDataPointer] used to write to the channel.
+    ///
+    /// The initial read-only `Data` counts as already consumed.
+    pub fn create(
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        LatestChannelPointer<Data>,
+        LatestDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        let mut channel_pointer = LatestChannelPointer {
+            channel: Box::new(LatestChannel {
+                channel: DirectedChannel::new(read_only, writable),
+                consumed: true,
+            }),
+        };
+        let channel: &mut LatestChannel<Data> = &mut channel_pointer.channel;
+        let latest_data_pointer = LatestDataPointer {
+            data: channel.channel.read_only_data_pointer(),
+            consumed: (&mut channel.consumed) as *mut bool,
+        };
+        let writable_data_pointer = channel.channel.writable_data_pointer();
+        (channel_pointer, latest_data_pointer, writable_data_pointer)
+    }
+
+    /// Destroys the latest-value channel linked with the given pointers (see [LatestChannel::create]).
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: LatestChannelPointer<Data>,
+        latest_data_pointer: LatestDataPointer<Data>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        let LatestChannelPointer { channel } = channel_pointer;
+        let LatestDataPointer {
+            data: ReadOnlyDataPointer {
+                data: read_only, ..
+            },
+            ..
+        } = latest_data_pointer;
+        let WritableDataPointer { data: writable, .. } = writable_data_pointer;
+
+        assert!(ptr::eq(&channel.channel.read_only.0, read_only.as_ptr()));
+        assert!(ptr::eq(&channel.channel.writable.0, writable.as_ptr()));
+
+        let LatestChannel { channel, .. } = *channel;
+        (channel.read_only.0, channel.writable.0)
+    }
+}
+
+impl<Data: Clone> LatestChannelPointer<Data> {
+    /// Clone the writable `Data` into the read-only `Data`, and mark it as fresh.
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        self.channel.channel.flush(channel_key);
+        self.channel.consumed = false;
+    }
+}
+
+impl<Data> LatestChannelPointer<Data> {
+    /// Shorthand for [LatestChannel::destroy].
+    pub fn destroy(
+        self,
+        latest_data_pointer: LatestDataPointer<Data>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        LatestChannel::destroy(self, latest_data_pointer, writable_data_pointer)
+    }
+}
+
+impl<Data> LatestDataPointer<Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer, regardless of whether it was already consumed.
+    pub fn get(&self, data_key: &DataKey) -> &Data {
+        self.data.get(data_key)
+    }
+
+    /// Get a reference to the `Data` field pointed to by this pointer, if it was not yet consumed since the last flush.
+    /// This marks the `Data` as consumed, so it returns `Some` at most once per flush.
+    pub fn take_if_fresh(&mut self, data_key: &DataKey) -> Option<&Data> {
+        let consumed = unsafe { &mut *self.consumed };
+        if *consumed {
+            None
+        } else {
+            *consumed = true;
+            Some(self.data.get(data_key))
+        }
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for LatestChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for LatestDataPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for LatestChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for LatestDataPointer<Data> {}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for LatestChannelPointer<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        LatestChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{directed::IDirectedChannel, latest::LatestChannel, MasterKey};
+
+    #[test]
+    fn test() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut latest_data_pointer, mut writable_data_pointer) =
+            LatestChannel::create(0, 0);
+        assert_eq!(
+            latest_data_pointer.take_if_fresh(&master_key.get_data_key()),
+            None
+        );
+
+        for i in 1..4 {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer.get_mut(&data_key) = i;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush(&channel_key);
+
+            let data_key = channel_key.into_data_key();
+            assert_eq!(latest_data_pointer.take_if_fresh(&data_key), Some(&i));
+            assert_eq!(latest_data_pointer.take_if_fresh(&data_key), None);
+            assert_eq!(*latest_data_pointer.get(&data_key), i);
+        }
+
+        let (read_only_data, writable_data) =
+            LatestChannel::destroy(channel_pointer, latest_data_pointer, writable_data_pointer);
+        assert_eq!(read_only_data, 3);
+        assert_eq!(writable_data, 3);
+    }
+
+    #[test]
+    fn ensure_channel_is_object_safe() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut latest_data_pointer, writable_data_pointer) =
+            LatestChannel::create(1, 2);
+        let dyn_channel_pointer: &mut dyn IDirectedChannel = &mut channel_pointer;
+
+        dyn_channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            latest_data_pointer.take_if_fresh(&master_key.get_data_key()),
+            Some(&2)
+        );
+        LatestChannel::destroy(channel_pointer, latest_data_pointer, writable_data_pointer);
+    }
+}
@@ -1,12 +1,50 @@
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
 use core::marker::PhantomData;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 static MASTER_KEY_EXISTS: AtomicBool = AtomicBool::new(false);
 
+pub mod accumulator;
+#[cfg(feature = "allocator_api")]
+pub mod allocator;
+pub mod arc;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod atomic_swap;
 pub mod bidirected;
+pub mod broadcast;
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod debounced;
+pub mod delta;
 pub mod directed;
+pub mod fan_in;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod graph;
+pub mod guard;
+pub mod handle;
+pub mod history;
+pub mod inline;
+pub mod latest;
+pub mod manager;
+pub mod mpsc_adapter;
+pub mod ordering;
+pub mod pipeline;
+pub mod pool;
+pub mod queued;
+pub mod scoped;
+pub mod seqlock;
+pub mod shared;
+pub mod slice;
+#[cfg(feature = "futures")]
+pub mod stream;
+pub mod triple_buffer;
 pub mod undirected;
+pub mod windowed;
 
 /// The master key.
 /// Only one instance of this type can exist at any time.
@@ -18,20 +56,56 @@ pub struct MasterKey {
     /// For debug purposes, multiple master keys can be created.
     /// To prevent them from interfering with the "real" master key, we mark them as "unlimited".
     unlimited: bool,
+    /// The maximum number of scoped keys derived from this master key that may exist simultaneously.
+    scoped_key_limit: usize,
+    /// The number of scoped keys derived from this master key that currently exist.
+    outstanding_keys: AtomicUsize,
+}
+
+/// Counts a scoped key towards the outstanding keys of its master key while it exists.
+#[derive(Debug)]
+struct KeyCount<'master_key> {
+    outstanding_keys: &'master_key AtomicUsize,
 }
 
 impl MasterKey {
     /// Creates a new master key.
     /// If there already is an existing master key, this function **panics**.
+    ///
+    /// See [`MasterKey::acquire`] for a non-panicking variant.
     pub fn create() -> Self {
-        // Assert that the master key does not exist
-        // and set it as existing.
-        // Using `Ordering::Relaxed` is fine here, since if the result is `true`,
-        // we anyways panic, and if the result is `false`, nothing happens.
-        assert!(!MASTER_KEY_EXISTS.swap(true, Ordering::Relaxed));
+        Self::acquire().expect("a master key already exists")
+    }
+
+    /// Creates a new master key that allows at most `limit` scoped keys to exist simultaneously,
+    /// see [`MasterKey::get_data_key_for`] and [`MasterKey::get_channel_key_for`].
+    /// Requesting more scoped keys **panics**, which helps to catch keys that are accidentally duplicated.
+    /// If there already is an existing master key, this function **panics**.
+    ///
+    /// The limit does not apply to the keys returned by [`MasterKey::get_data_key`] and [`MasterKey::get_channel_key`],
+    /// since they mutably borrow the master key, so there is never more than one of them anyways.
+    ///
+    /// See [`MasterKey::outstanding_keys`].
+    pub fn create_with_scoped_key_limit(limit: usize) -> Self {
+        let mut master_key = Self::create();
+        master_key.scoped_key_limit = limit;
+        master_key
+    }
 
-        // Return a new master key.
-        Self { unlimited: false }
+    /// Creates a new master key if there is no existing master key, and returns `None` otherwise.
+    /// After the existing master key is dropped, a new one can be acquired again.
+    pub fn acquire() -> Option<Self> {
+        // Set the master key as existing if it does not exist.
+        // `Ordering::Acquire` pairs with the `Ordering::Release` when dropping the previous master key,
+        // such that all accesses made under the previous master key happen before accesses under the new one.
+        if MASTER_KEY_EXISTS
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            Some(Self::new(false))
+        } else {
+            None
+        }
     }
 
     /// Creates a new master key without checking if there already is one.
@@ -44,7 +118,40 @@ impl MasterKey {
     ///
     /// Use this only for testing and debugging purposes.
     pub unsafe fn create_unlimited() -> Self {
-        Self { unlimited: true }
+        Self::new(true)
+    }
+
+    fn new(unlimited: bool) -> Self {
+        Self {
+            unlimited,
+            scoped_key_limit: usize::MAX,
+            outstanding_keys: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of keys derived from this master key that currently exist.
+    /// Since keys that mutably borrow the master key prevent calling this method, this counts only scoped keys,
+    /// see [`MasterKey::get_data_key_for`] and [`MasterKey::get_channel_key_for`].
+    pub fn outstanding_keys(&self) -> usize {
+        self.outstanding_keys.load(Ordering::Relaxed)
+    }
+
+    /// Count a new scoped key towards the outstanding keys.
+    ///
+    /// **Panics** if this would exceed the scoped key limit of this master key.
+    fn count_key(&self) -> KeyCount<'_> {
+        assert!(
+            self.outstanding_keys
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |outstanding_keys| {
+                    (outstanding_keys < self.scoped_key_limit).then(|| outstanding_keys + 1)
+                })
+                .is_ok(),
+            "the limit of {} outstanding scoped keys is exceeded",
+            self.scoped_key_limit
+        );
+        KeyCount {
+            outstanding_keys: &self.outstanding_keys,
+        }
     }
 
     /// Get a unique data key from this master key.
@@ -64,15 +171,20 @@ impl MasterKey {
     }
 }
 
+impl<'master_key> Drop for KeyCount<'master_key> {
+    fn drop(&mut self) {
+        self.outstanding_keys.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl Drop for MasterKey {
     /// After dropping a master key, a new one can be created again.
     fn drop(&mut self) {
         if !self.unlimited {
             // Assert that the master key exists
             // and set it as not existing.
-            // Using `Ordering::Relaxed` is fine here, since if the result is `true`,
-            // we anyways panic, and if the result is `false`, nothing happens.
-            assert!(MASTER_KEY_EXISTS.swap(false, Ordering::Relaxed));
+            // `Ordering::Release` pairs with the `Ordering::Acquire` in `MasterKey::acquire`.
+            assert!(MASTER_KEY_EXISTS.swap(false, Ordering::Release));
         }
     }
 }
@@ -95,6 +207,13 @@ impl<'master_key> DataKey<'master_key> {
     pub fn into_channel_key(self) -> ChannelKey<'master_key> {
         ChannelKey { scope: self.scope }
     }
+
+    /// Run the given closure in the read phase, and convert this data key into a channel key afterwards.
+    /// Together with [`ChannelKey::in_flush_phase`], this encodes the alternation of the phases in the structure of the code.
+    pub fn in_read_phase<R>(self, f: impl FnOnce(&DataKey) -> R) -> (ChannelKey<'master_key>, R) {
+        let result = f(&self);
+        (self.into_channel_key(), result)
+    }
 }
 
 impl<'master_key> ChannelKey<'master_key> {
@@ -103,4 +222,154 @@ impl<'master_key> ChannelKey<'master_key> {
     pub fn into_data_key(self) -> DataKey<'master_key> {
         DataKey { scope: self.scope }
     }
+
+    /// Run the given closure in the flush phase, and convert this channel key into a data key afterwards.
+    ///
+    /// See [`DataKey::in_read_phase`] for more info.
+    pub fn in_flush_phase<R>(self, f: impl FnOnce(&ChannelKey) -> R) -> (DataKey<'master_key>, R) {
+        let result = f(&self);
+        (self.into_data_key(), result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Mutex, MutexGuard};
+
+    use crate::{directed::DirectedChannel, scoped::ChannelId, MasterKey};
+
+    /// Tests that create a limited master key must hold this lock, since only one such key can exist at a time.
+    static MASTER_KEY_LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock_master_key() -> MutexGuard<'static, ()> {
+        // Tests that panic poison the lock, but the master key is released anyways when unwinding.
+        MASTER_KEY_LOCK
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    #[test]
+    fn test_acquire() {
+        let _lock = lock_master_key();
+        let master_key = MasterKey::acquire().unwrap();
+        assert!(MasterKey::acquire().is_none());
+        drop(master_key);
+
+        let master_key = MasterKey::acquire();
+        assert!(master_key.is_some());
+    }
+
+    #[test]
+    fn test_reacquire_after_drop() {
+        let _lock = lock_master_key();
+        for _ in 0..3 {
+            let mut master_key = MasterKey::acquire().unwrap();
+            master_key.get_data_key();
+            drop(master_key);
+        }
+
+        let master_key = MasterKey::create();
+        drop(master_key);
+        assert!(MasterKey::acquire().is_some());
+    }
+
+    #[test]
+    fn test_outstanding_keys() {
+        let _lock = lock_master_key();
+        let mut master_key = MasterKey::create_with_scoped_key_limit(2);
+        assert_eq!(master_key.outstanding_keys(), 0);
+        master_key.get_data_key().into_channel_key();
+        assert_eq!(master_key.outstanding_keys(), 0);
+
+        let id = ChannelId::new();
+        let data_key1 = master_key.get_data_key_for(&id);
+        let data_key2 = master_key.get_data_key_for(&id);
+        assert_eq!(master_key.outstanding_keys(), 2);
+        drop((data_key1, data_key2));
+        assert_eq!(master_key.outstanding_keys(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the limit of 2 outstanding scoped keys is exceeded")]
+    fn test_exceed_key_limit() {
+        let _lock = lock_master_key();
+        let master_key = MasterKey::create_with_scoped_key_limit(2);
+        let id = ChannelId::new();
+        let _data_key1 = master_key.get_data_key_for(&id);
+        let _data_key2 = master_key.get_data_key_for(&id);
+        let _data_key3 = master_key.get_data_key_for(&id);
+    }
+
+    #[test]
+    #[should_panic(expected = "the limit of 0 outstanding scoped keys is exceeded")]
+    fn test_exceed_zero_key_limit() {
+        let _lock = lock_master_key();
+        let master_key = MasterKey::create_with_scoped_key_limit(0);
+        master_key.get_channel_key_for(&ChannelId::new());
+    }
+
+    #[test]
+    fn test_key_limit_ignores_unscoped_keys() {
+        let _lock = lock_master_key();
+        let mut master_key = MasterKey::create_with_scoped_key_limit(0);
+        for _ in 0..3 {
+            master_key.get_data_key().into_channel_key();
+            master_key.get_channel_key();
+        }
+        assert_eq!(master_key.outstanding_keys(), 0);
+    }
+
+    #[test]
+    fn test_phase_round_trip() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+
+        let mut data_key = master_key.get_data_key();
+        for i in 0..3 {
+            assert_eq!(*read_only_data_pointer.get(&data_key), i);
+            *writable_data_pointer.get_mut(&data_key) += 1;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush(&channel_key);
+            data_key = channel_key.into_data_key();
+        }
+        assert_eq!(*read_only_data_pointer.get(&data_key), 3);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+
+    #[test]
+    fn test_phase_closures() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+
+        let mut data_key = master_key.get_data_key();
+        for i in 0..3 {
+            let (channel_key, value) = data_key.in_read_phase(|data_key| {
+                *writable_data_pointer.get_mut(data_key) += 1;
+                *read_only_data_pointer.get(data_key)
+            });
+            assert_eq!(value, i);
+
+            let (next_data_key, flush_count) = channel_key.in_flush_phase(|channel_key| {
+                channel_pointer.flush(channel_key);
+                channel_pointer.flush_count()
+            });
+            assert_eq!(flush_count, i + 1);
+            data_key = next_data_key;
+        }
+        assert_eq!(*read_only_data_pointer.get(&data_key), 3);
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
 }
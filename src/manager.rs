@@ -0,0 +1,209 @@
+//! A manager that flushes many directed channels of different `Data` types at once.
+
+use std::{
+    any::Any,
+    fmt::{self, Debug, Formatter},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+
+use crate::{directed::IDirectedChannel, ChannelKey};
+
+/// Owns directed channels of arbitrary types and flushes them all together,
+/// e.g. once per tick of a simulation.
+///
+/// The channels are registered via [`DirectedChannelManager::register`],
+/// and must be taken out again via [`DirectedChannelManager::unregister`] to destroy them.
+#[derive(Default)]
+pub struct DirectedChannelManager {
+    channels: Vec<Option<Box<dyn ManagedChannel>>>,
+}
+
+/// An [`IDirectedChannel`] that can be taken out of the manager as its concrete type.
+pub(crate) trait ManagedChannel: IDirectedChannel {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<Channel: IDirectedChannel + 'static> ManagedChannel for Channel {
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Take the channel with the given index out of the given channels as its concrete type.
+///
+/// **Panics** if there is no channel with the given index, or if it is not of type `Channel`.
+pub(crate) fn take_channel<Channel: IDirectedChannel + 'static>(
+    channels: &mut [Option<Box<dyn ManagedChannel>>],
+    index: usize,
+) -> Channel {
+    let channel = channels
+        .get_mut(index)
+        .and_then(Option::take)
+        .unwrap_or_else(|| panic!("there is no channel with index {}", index));
+    match channel.into_any().downcast() {
+        Ok(channel) => *channel,
+        Err(_) => panic!("the channel with index {} has a different type", index),
+    }
+}
+
+impl DirectedChannelManager {
+    /// Create a manager without any channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the given channel with this manager, such that it is flushed by [`DirectedChannelManager::flush_all`].
+    /// Returns the index of the channel, which stays valid until the channel is unregistered.
+    pub fn register(&mut self, channel: impl IDirectedChannel + 'static) -> usize {
+        self.channels.push(Some(Box::new(channel)));
+        self.channels.len() - 1
+    }
+
+    /// Take the channel with the given index out of this manager, e.g. to destroy it.
+    ///
+    /// **Panics** if there is no channel with the given index, or if it is not of type `Channel`.
+    pub fn unregister<Channel: IDirectedChannel + 'static>(&mut self, index: usize) -> Channel {
+        take_channel(&mut self.channels, index)
+    }
+
+    /// The number of registered channels.
+    pub fn len(&self) -> usize {
+        self.channels
+            .iter()
+            .filter(|channel| channel.is_some())
+            .count()
+    }
+
+    /// Returns `true` if there are no registered channels.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Flush all registered channels.
+    pub fn flush_all(&mut self, channel_key: &ChannelKey) {
+        for channel in self.channels.iter_mut().flatten() {
+            channel.flush(channel_key);
+        }
+    }
+
+    /// Flush all registered channels in parallel using the global [rayon] thread pool.
+    /// Since the channels are disjoint, they can be flushed independently during the same phase.
+    #[cfg(feature = "rayon")]
+    pub fn flush_all_parallel(&mut self, channel_key: &ChannelKey) {
+        self.channels
+            .par_iter_mut()
+            .flatten()
+            .for_each(|channel| channel.flush(channel_key));
+    }
+}
+
+impl Debug for DirectedChannelManager {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("DirectedChannelManager")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        directed::{DirectedChannel, DirectedChannelPointer},
+        manager::DirectedChannelManager,
+        MasterKey,
+    };
+
+    #[test]
+    fn test_flush_all() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut manager = DirectedChannelManager::new();
+        let (channel_pointer1, read_only_data_pointer1, mut writable_data_pointer1) =
+            DirectedChannel::create(0, 0);
+        let (channel_pointer2, read_only_data_pointer2, mut writable_data_pointer2) =
+            DirectedChannel::create(String::new(), String::new());
+        let index1 = manager.register(channel_pointer1);
+        let index2 = manager.register(channel_pointer2);
+        assert_eq!(manager.len(), 2);
+
+        {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer1.get_mut(&data_key) = 1;
+            writable_data_pointer2.get_mut(&data_key).push_str("one");
+        }
+        manager.flush_all(&master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        assert_eq!(*read_only_data_pointer1.get(&data_key), 1);
+        assert_eq!(read_only_data_pointer2.get(&data_key), "one");
+
+        let channel_pointer2: DirectedChannelPointer<String> = manager.unregister(index2);
+        let channel_pointer1: DirectedChannelPointer<i32> = manager.unregister(index1);
+        assert!(manager.is_empty());
+        channel_pointer1.destroy_single(read_only_data_pointer1, writable_data_pointer1);
+        channel_pointer2.destroy_single(read_only_data_pointer2, writable_data_pointer2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_flush_all_parallel() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut manager = DirectedChannelManager::new();
+        let mut pointers: Vec<_> = (0..100)
+            .map(|_| {
+                let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+                    DirectedChannel::create(vec![0; 1000], vec![0; 1000]);
+                (
+                    manager.register(channel_pointer),
+                    read_only_data_pointer,
+                    writable_data_pointer,
+                )
+            })
+            .collect();
+
+        {
+            let data_key = master_key.get_data_key();
+            for (index, _, writable_data_pointer) in &mut pointers {
+                writable_data_pointer.get_mut(&data_key).fill(*index);
+            }
+        }
+        manager.flush_all_parallel(&master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        for (index, read_only_data_pointer, _) in &pointers {
+            assert!(read_only_data_pointer
+                .get(&data_key)
+                .iter()
+                .all(|value| value == index));
+        }
+        for (index, read_only_data_pointer, writable_data_pointer) in pointers {
+            manager
+                .unregister::<DirectedChannelPointer<Vec<usize>>>(index)
+                .destroy_single(read_only_data_pointer, writable_data_pointer);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "has a different type")]
+    fn test_unregister_wrong_type() {
+        let mut manager = DirectedChannelManager::new();
+        let (channel_pointer, _read_only_data_pointer, _writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let index = manager.register(channel_pointer);
+        let _: DirectedChannelPointer<u8> = manager.unregister(index);
+    }
+}
@@ -0,0 +1,138 @@
+//! A facade with an API similar to [`std::sync::mpsc`], backed by a directed channel.
+//! Unlike a queue, the receiver always gets the latest value that was sent.
+//!
+//! This is meant to ease the migration from message passing to two-phase channels.
+//! Since the sender and the receiver share a single [`MasterKey`], all accesses are serialised,
+//! so use the channels in [`crate::directed`] directly to profit from the two phases.
+
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    MasterKey,
+};
+
+/// The sending half of a channel created by [`channel`].
+/// It can be cloned to send from multiple threads.
+pub struct Sender<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+/// The receiving half of a channel created by [`channel`].
+pub struct Receiver<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+struct Inner<T> {
+    master_key: MasterKey,
+    pointers: Option<(
+        DirectedChannelPointer<T>,
+        ReadOnlyDataPointer<T>,
+        WritableDataPointer<T>,
+    )>,
+}
+
+/// Create a channel whose receiver initially receives `T::default()`.
+pub fn channel<T: Clone + Default>() -> (Sender<T>, Receiver<T>) {
+    channel_with(T::default())
+}
+
+/// Create a channel whose receiver initially receives a clone of the given value.
+pub fn channel_with<T: Clone>(initial: T) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Mutex::new(Inner {
+        // The master key never leaves the channel, so it only gates the pointers of this channel,
+        // and the mutex ensures that its keys are never used at the same time.
+        master_key: unsafe { MasterKey::create_unlimited() },
+        pointers: Some(DirectedChannel::create(initial.clone(), initial)),
+    }));
+    (
+        Sender {
+            inner: inner.clone(),
+        },
+        Receiver { inner },
+    )
+}
+
+impl<T: Clone> Sender<T> {
+    /// Send a value, replacing the value that the receiver gets.
+    pub fn send(&mut self, value: T) {
+        let mut inner = self.inner.lock().unwrap();
+        let Inner {
+            master_key,
+            pointers,
+        } = &mut *inner;
+        let (channel_pointer, _, writable_data_pointer) = pointers.as_mut().unwrap();
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = value;
+        channel_pointer.flush(&master_key.get_channel_key());
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receive a clone of the latest value that was sent.
+    /// This never blocks for longer than a concurrent [`Sender::send`].
+    pub fn recv(&self) -> T {
+        let mut inner = self.inner.lock().unwrap();
+        let Inner {
+            master_key,
+            pointers,
+        } = &mut *inner;
+        let (_, read_only_data_pointer, _) = pointers.as_ref().unwrap();
+        read_only_data_pointer
+            .get(&master_key.get_data_key())
+            .clone()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        if let Some((channel_pointer, read_only_data_pointer, writable_data_pointer)) =
+            self.pointers.take()
+        {
+            DirectedChannel::destroy_single(
+                channel_pointer,
+                read_only_data_pointer,
+                writable_data_pointer,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::mpsc_adapter::channel;
+
+    #[test]
+    fn test_receive_latest() {
+        let (mut sender, receiver) = channel();
+        assert_eq!(receiver.recv(), 0);
+
+        for i in 1..=3 {
+            sender.send(i);
+        }
+        assert_eq!(receiver.recv(), 3);
+        assert_eq!(receiver.recv(), 3);
+    }
+
+    #[test]
+    fn test_send_from_threads() {
+        let (sender, receiver) = channel();
+        thread::scope(|scope| {
+            for i in 1..=3 {
+                let mut sender = sender.clone();
+                scope.spawn(move || sender.send(i));
+            }
+        });
+        drop(sender);
+        assert!((1..=3).contains(&receiver.recv()));
+    }
+}
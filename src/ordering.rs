@@ -0,0 +1,94 @@
+//! The memory ordering of the lock-free channels of this crate, i.e. [`AtomicSwapChannel`](crate::atomic_swap::AtomicSwapChannel)
+//! and [`SeqlockChannel`](crate::seqlock::SeqlockChannel).
+//!
+//! All orderings are sound, they only differ in how the synchronisation is achieved,
+//! which has different costs depending on the platform.
+
+use std::sync::atomic::Ordering;
+#[cfg(not(loom))]
+pub(crate) use std::{
+    hint,
+    sync::{
+        atomic::{fence, AtomicPtr, AtomicU64},
+        Arc,
+    },
+};
+
+#[cfg(loom)]
+pub(crate) use loom::{
+    hint,
+    sync::{
+        atomic::{fence, AtomicPtr, AtomicU64},
+        Arc,
+    },
+};
+
+/// How the atomic operations of a lock-free channel are ordered, chosen at the construction of the channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryOrdering {
+    /// All atomic operations are relaxed, and the channel synchronises via explicit fences instead.
+    /// This may be cheaper on platforms where fences are cheaper than acquire loads and release stores.
+    Relaxed,
+    /// Loads acquire and stores release, with explicit fences only where a store must be ordered before a later load.
+    /// This is the default.
+    AcquireRelease,
+    /// All atomic operations are sequentially consistent, and no explicit fences are needed.
+    SeqCst,
+}
+
+impl Default for MemoryOrdering {
+    fn default() -> Self {
+        Self::AcquireRelease
+    }
+}
+
+impl MemoryOrdering {
+    /// The ordering of loads that synchronise with a store of another thread.
+    pub(crate) fn load(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcquireRelease => Ordering::Acquire,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// The ordering of stores that synchronise with a load of another thread.
+    pub(crate) fn store(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcquireRelease => Ordering::Release,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// The ordering of read-modify-write operations that synchronise in both directions.
+    pub(crate) fn read_modify_write(self) -> Ordering {
+        match self {
+            Self::Relaxed => Ordering::Relaxed,
+            Self::AcquireRelease => Ordering::AcqRel,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// Issue an acquire fence after a [`MemoryOrdering::load`], if the load itself does not acquire.
+    pub(crate) fn acquire_fence(self) {
+        if self == Self::Relaxed {
+            fence(Ordering::Acquire);
+        }
+    }
+
+    /// Issue a release fence before a [`MemoryOrdering::store`], if the store itself does not release.
+    pub(crate) fn release_fence(self) {
+        if self == Self::Relaxed {
+            fence(Ordering::Release);
+        }
+    }
+
+    /// Issue a sequentially consistent fence between a store and a later load of a different atomic,
+    /// if the operations themselves are not sequentially consistent.
+    pub(crate) fn store_load_fence(self) {
+        if self != Self::SeqCst {
+            fence(Ordering::SeqCst);
+        }
+    }
+}
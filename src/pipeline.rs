@@ -0,0 +1,217 @@
+//! A pipeline two-phase channel.
+//! The channel provides one writable data pointer for the input stage and one read-only data pointer per stage output.
+//! On each flush, the data advances by one stage, like in a shift register.
+
+use std::ptr::{self, NonNull};
+
+use crate::{
+    directed::{IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey,
+};
+
+/// A pipeline channel used for communication between threads.
+/// It holds one writable instance of `Data` as input, and a fixed number of read-only instances of `Data` as stage outputs.
+/// A flush moves the `Data` of each stage into the next stage, and copies the input into the first stage.
+///
+/// This models a fixed-latency pipeline, where readers at later stages see progressively older data.
+///
+/// See [PipelineChannel::create] for more info.
+#[derive(Debug)]
+pub struct PipelineChannel<Data> {
+    stages: Vec<Data>,
+    version: u64,
+    input: Data,
+    dirty: bool,
+}
+
+/// A pointer to a pipeline channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [PipelineChannel::destroy] or [PipelineChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct PipelineChannelPointer<Data> {
+    channel: Box<PipelineChannel<Data>>,
+}
+
+impl<Data: Clone> PipelineChannel<Data> {
+    /// Create a pipeline channel with `n` stages and hand out pointers to it.
+    /// One [PipelineChannelPointer] used to advance the pipeline,
+    /// one [WritableDataPointer] used to write to the input stage, and
+    /// `n` [ReadOnlyDataPointer]s used to read the output of each stage, in order.
+    ///
+    /// The input and all stages are initialised as clones of `init`.
+    pub fn create(
+        n: usize,
+        init: Data,
+    ) -> (
+        PipelineChannelPointer<Data>,
+        WritableDataPointer<Data>,
+        Vec<ReadOnlyDataPointer<Data>>,
+    ) {
+        let mut channel_pointer = PipelineChannelPointer {
+            channel: Box::new(PipelineChannel {
+                stages: vec![init.clone(); n],
+                version: 0,
+                input: init,
+                dirty: false,
+            }),
+        };
+        let channel: &mut PipelineChannel<Data> = &mut channel_pointer.channel;
+        let writable_data_pointer = WritableDataPointer {
+            data: NonNull::from(&mut channel.input),
+            dirty: (&mut channel.dirty) as *mut bool,
+        };
+        let read_only_data_pointers = channel
+            .stages
+            .iter()
+            .map(|stage| ReadOnlyDataPointer {
+                data: NonNull::from(stage),
+                version: (&channel.version) as *const u64,
+            })
+            .collect();
+        (
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        )
+    }
+}
+
+impl<Data> PipelineChannel<Data> {
+    /// Destroys the pipeline channel linked with the given pointers (see [PipelineChannel::create]).
+    /// Returns the input `Data` and the `Data` of each stage in order.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: PipelineChannelPointer<Data>,
+        writable_data_pointer: WritableDataPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+    ) -> (Data, Vec<Data>) {
+        let PipelineChannelPointer { mut channel } = channel_pointer;
+        let channel_writable_data_pointer = NonNull::from(&mut channel.input);
+        let WritableDataPointer {
+            data: writable_data_pointer,
+            ..
+        } = writable_data_pointer;
+        assert_eq!(channel_writable_data_pointer, writable_data_pointer);
+
+        for read_only_data_pointer in read_only_data_pointers {
+            let ReadOnlyDataPointer {
+                data: read_only_data_pointer,
+                ..
+            } = read_only_data_pointer;
+            assert!(channel
+                .stages
+                .iter()
+                .any(|stage| ptr::eq(stage, read_only_data_pointer.as_ptr())));
+        }
+
+        (channel.input, channel.stages)
+    }
+}
+
+impl<Data: Clone> PipelineChannelPointer<Data> {
+    /// Advance the pipeline by one stage.
+    /// The `Data` of each stage is moved into the next stage, the `Data` of the last stage is dropped,
+    /// and the input `Data` is cloned into the first stage.
+    pub fn flush(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel: &mut PipelineChannel<Data> = &mut self.channel;
+        if !channel.stages.is_empty() {
+            // Clone before rotating, such that the stages stay unchanged if the clone panics.
+            let input = channel.input.clone();
+            channel.stages.rotate_right(1);
+            channel.stages[0] = input;
+        }
+        channel.version += 1;
+        channel.dirty = false;
+    }
+}
+
+impl<Data> PipelineChannelPointer<Data> {
+    /// The number of stages of this pipeline channel.
+    pub fn stage_count(&self) -> usize {
+        self.channel.stages.len()
+    }
+
+    /// Shorthand for [PipelineChannel::destroy].
+    pub fn destroy(
+        self,
+        writable_data_pointer: WritableDataPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+    ) -> (Data, Vec<Data>) {
+        PipelineChannel::destroy(self, writable_data_pointer, read_only_data_pointers)
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for PipelineChannelPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for PipelineChannelPointer<Data> {}
+
+impl<Data: Clone + Send + Sync> IDirectedChannel for PipelineChannelPointer<Data> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        PipelineChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{directed::IDirectedChannel, pipeline::PipelineChannel, MasterKey};
+
+    #[test]
+    fn test() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut writable_data_pointer, read_only_data_pointers) =
+            PipelineChannel::create(3, -1);
+        assert_eq!(channel_pointer.stage_count(), 3);
+
+        let expected_outputs = [[0, -1, -1], [1, 0, -1], [2, 1, 0], [3, 2, 1]];
+        for (i, expected_output) in expected_outputs.iter().enumerate() {
+            let data_key = master_key.get_data_key();
+            *writable_data_pointer.get_mut(&data_key) = i as i32;
+
+            let channel_key = data_key.into_channel_key();
+            channel_pointer.flush(&channel_key);
+
+            let data_key = channel_key.into_data_key();
+            let output: Vec<_> = read_only_data_pointers
+                .iter()
+                .map(|pointer| *pointer.get(&data_key))
+                .collect();
+            assert_eq!(&output, expected_output);
+            assert_eq!(read_only_data_pointers[2].version(&data_key), i as u64 + 1);
+        }
+
+        let (input, stages) = PipelineChannel::destroy(
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        );
+        assert_eq!(input, 3);
+        assert_eq!(stages, [3, 2, 1]);
+    }
+
+    #[test]
+    fn ensure_channel_is_object_safe() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, mut writable_data_pointer, read_only_data_pointers) =
+            PipelineChannel::create(2, 1);
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 2;
+        let dyn_channel_pointer: &mut dyn IDirectedChannel = &mut channel_pointer;
+
+        dyn_channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(
+            *read_only_data_pointers[0].get(&master_key.get_data_key()),
+            2
+        );
+        assert_eq!(
+            *read_only_data_pointers[1].get(&master_key.get_data_key()),
+            1
+        );
+        PipelineChannel::destroy(
+            channel_pointer,
+            writable_data_pointer,
+            read_only_data_pointers,
+        );
+    }
+}
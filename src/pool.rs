@@ -0,0 +1,129 @@
+//! A pool of the allocations of destroyed directed channels,
+//! for workloads that create and destroy channels frequently.
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::directed::{
+    DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer,
+};
+
+/// Keeps the allocations of channels destroyed via [`ChannelPool::destroy`],
+/// and hands them out again via [`ChannelPool::create`] instead of allocating a new channel.
+///
+/// The `Data` fields of a destroyed channel are kept as well, after passing them to the `reset` function of the pool.
+/// This way, `Data` that owns memory itself, like a `Vec`, can keep its capacity.
+pub struct ChannelPool<Data> {
+    channels: Vec<Box<DirectedChannel<Data>>>,
+    reset: Box<dyn Fn(&mut Data) + Send + Sync>,
+}
+
+impl<Data> ChannelPool<Data> {
+    /// Create an empty pool that resets the `Data` fields of destroyed channels via `reset`.
+    pub fn new(reset: impl Fn(&mut Data) + Send + Sync + 'static) -> Self {
+        Self {
+            channels: Vec::new(),
+            reset: Box::new(reset),
+        }
+    }
+
+    /// The number of channels that can be created without allocating.
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns `true` if the next channel created has to be allocated.
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Destroys the directed channel linked with the given pointers (see [DirectedChannel::create]),
+    /// resets its `Data` fields and keeps it for the next [`ChannelPool::create`].
+    ///
+    /// **Panics** if not all pointers point to the same channel,
+    /// or if fewer read-only data pointers are passed than were handed out (see [`DirectedChannelPointer::reader_count`]).
+    pub fn destroy(
+        &mut self,
+        channel_pointer: DirectedChannelPointer<Data>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) {
+        let mut channel = DirectedChannel::try_destroy_boxed(
+            channel_pointer,
+            read_only_data_pointers,
+            writable_data_pointer,
+        )
+        .unwrap_or_else(|error| panic!("{}", error));
+        (self.reset)(&mut channel.read_only.0);
+        (self.reset)(&mut channel.writable.0);
+        channel.recycle();
+        self.channels.push(channel);
+    }
+}
+
+impl<Data: Default> ChannelPool<Data> {
+    /// Create a directed channel and hand out three pointers to it (see [DirectedChannel::create]).
+    /// If the pool holds a destroyed channel, it is reused with its reset `Data` fields.
+    /// Otherwise, a new channel with default `Data` is allocated.
+    pub fn create(
+        &mut self,
+    ) -> (
+        DirectedChannelPointer<Data>,
+        ReadOnlyDataPointer<Data>,
+        WritableDataPointer<Data>,
+    ) {
+        match self.channels.pop() {
+            Some(channel) => DirectedChannel::into_pointers(channel),
+            None => DirectedChannel::create_default(),
+        }
+    }
+}
+
+impl<Data> Debug for ChannelPool<Data> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ChannelPool")
+            .field("channels", &self.channels.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::ChannelPool, MasterKey};
+
+    #[test]
+    fn test_reuse_allocation() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let mut pool = ChannelPool::new(Vec::<i32>::clear);
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            pool.create();
+        let address = read_only_data_pointer.as_ptr();
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .extend(0..100);
+        channel_pointer.flush(&master_key.get_channel_key());
+        pool.destroy(
+            channel_pointer,
+            [read_only_data_pointer],
+            writable_data_pointer,
+        );
+        assert_eq!(pool.len(), 1);
+
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) = pool.create();
+        assert!(pool.is_empty());
+        assert_eq!(read_only_data_pointer.as_ptr(), address);
+        assert_eq!(channel_pointer.flush_count(), 0);
+        assert_eq!(channel_pointer.reader_count(), 1);
+        let data_key = master_key.get_data_key();
+        assert!(read_only_data_pointer.get(&data_key).is_empty());
+        assert!(writable_data_pointer.get(&data_key).capacity() >= 100);
+        channel_pointer.flush(&master_key.get_channel_key());
+        assert_eq!(channel_pointer.flush_count(), 1);
+
+        pool.destroy(
+            channel_pointer,
+            [read_only_data_pointer],
+            writable_data_pointer,
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! A queued two-phase channel.
+//! Unlike a directed channel, which only keeps the latest flushed `Data`,
+//! it keeps a bounded queue of flushed `Data`, such that a slow reader does not miss intermediate values.
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    ptr::{self, NonNull},
+    sync::{Mutex, MutexGuard},
+};
+
+#[cfg(feature = "std")]
+use std::sync::Condvar;
+
+use crate::{
+    directed::{check_destroy, WritableDataPointer},
+    ChannelKey,
+};
+
+/// What a [`QueuedChannel`] does when it is flushed while its queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest `Data` in the queue to make space for the flushed `Data`.
+    DropOldest,
+    /// Reject the flush with a [`QueueFullError`].
+    Reject,
+}
+
+/// The error returned by [`QueuedChannel::flush`] if the queue is full and the overflow policy is [`OverflowPolicy::Reject`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFullError {
+    /// The capacity of the full queue.
+    pub capacity: usize,
+}
+
+impl Display for QueueFullError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "the queue is full with {} entries", self.capacity)
+    }
+}
+
+impl Error for QueueFullError {}
+
+/// The pointers of a queued channel only ever reference the fields of their side:
+/// the writable `Data` is only accessed in phases, while the queue is shared by all pointers at any time.
+#[derive(Debug)]
+struct Queue<Data> {
+    writable: Data,
+    dirty: bool,
+    queue: Mutex<VecDeque<Data>>,
+    /// Signaled whenever an entry is dequeued.
+    #[cfg(feature = "std")]
+    dequeued: Condvar,
+}
+
+/// A pointer to a queued channel, i.e. a channel that enqueues the writable `Data` on every flush,
+/// from where it can be dequeued by readers in order.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [QueuedChannel::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct QueuedChannel<Data> {
+    /// Allocated via [`Box`], and freed in [`QueuedChannel::destroy`].
+    channel: NonNull<Queue<Data>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+/// A pointer to the queue of a queued channel.
+/// Unlike the other pointers of this crate, it needs no key, since the queue is guarded by a mutex,
+/// so the channel can be flushed while readers dequeue.
+///
+/// All copies of this pointer dequeue from the same queue, so each `Data` is received by only one of them.
+///
+/// This type should always be destroyed via the [QueuedChannel::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct QueuedDataPointer<Data> {
+    /// Never null, since it always points into a channel.
+    queue: NonNull<Queue<Data>>,
+}
+
+impl<Data> QueuedChannel<Data> {
+    /// Create a queued channel with a queue of `capacity` entries, and hand out three pointers to it.
+    /// One [QueuedChannel] used to enqueue the writable `Data`,
+    /// one [QueuedDataPointer] used to dequeue from the channel, and
+    /// one [WritableDataPointer] used to write to the channel.
+    ///
+    /// **Panics** if the capacity is zero.
+    pub fn create(
+        capacity: usize,
+        policy: OverflowPolicy,
+        writable: Data,
+    ) -> (Self, QueuedDataPointer<Data>, WritableDataPointer<Data>) {
+        assert!(capacity > 0, "the capacity of a queue must not be zero");
+        let channel = Box::into_raw(Box::new(Queue {
+            writable,
+            dirty: false,
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            #[cfg(feature = "std")]
+            dequeued: Condvar::new(),
+        }));
+        let queued_data_pointer = QueuedDataPointer {
+            queue: unsafe { NonNull::new_unchecked(channel) },
+        };
+        let writable_data_pointer = WritableDataPointer {
+            data: unsafe { NonNull::new_unchecked(ptr::addr_of_mut!((*channel).writable)) },
+            dirty: unsafe { ptr::addr_of_mut!((*channel).dirty) },
+        };
+        let channel = unsafe { NonNull::new_unchecked(channel) };
+        (
+            Self {
+                channel,
+                capacity,
+                policy,
+            },
+            queued_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// The maximum number of entries in the queue.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The overflow policy of this channel.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Returns true if the queue is full, i.e. if the next flush would drop the oldest entry or be rejected.
+    /// A writer can use this to apply backpressure before flushing.
+    pub fn is_full(&self) -> bool {
+        self.remaining_capacity() == 0
+    }
+
+    /// The number of entries that can be flushed before the queue is full.
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity - unsafe { Queue::lock(self.channel) }.len()
+    }
+
+    /// Destroys the queued channel linked with the given pointers (see [QueuedChannel::create]).
+    /// Returns the `Data` remaining in the queue, from the oldest to the newest, and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        self,
+        queued_data_pointers: impl IntoIterator<Item = QueuedDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Vec<Data>, Data) {
+        let Self { channel, .. } = self;
+        // The queued data pointers point to the whole queue instead of a read-only `Data`.
+        if let Some(kind) = check_destroy(
+            channel.as_ptr() as *const u8,
+            unsafe { ptr::addr_of!((*channel.as_ptr()).writable) }.cast(),
+            0,
+            &mut queued_data_pointers
+                .into_iter()
+                .map(|queued_data_pointer| queued_data_pointer.queue.as_ptr() as *const u8),
+            writable_data_pointer.data.as_ptr() as *const u8,
+        ) {
+            panic!("{}", kind);
+        }
+
+        let Queue {
+            writable, queue, ..
+        } = *unsafe { Box::from_raw(channel.as_ptr()) };
+        let queue = queue
+            .into_inner()
+            .unwrap_or_else(|error| error.into_inner());
+        (queue.into(), writable)
+    }
+}
+
+impl<Data: Clone> QueuedChannel<Data> {
+    /// Enqueue a clone of the writable `Data`.
+    ///
+    /// If the queue is full, this drops the oldest entry or returns an error, depending on the [`OverflowPolicy`].
+    pub fn flush(
+        &mut self,
+        #[allow(unused)] channel_key: &ChannelKey,
+    ) -> Result<(), QueueFullError> {
+        let channel = self.channel.as_ptr();
+        let mut queue = unsafe { Queue::lock(self.channel) };
+        if queue.len() == self.capacity {
+            match self.policy {
+                OverflowPolicy::DropOldest => {
+                    queue.pop_front();
+                }
+                OverflowPolicy::Reject => {
+                    return Err(QueueFullError {
+                        capacity: self.capacity,
+                    })
+                }
+            }
+        }
+        // The writable `Data` is only accessed in phases, so the channel key grants exclusive access to it.
+        queue.push_back(unsafe { (*channel).writable.clone() });
+        drop(queue);
+        unsafe { (*channel).dirty = false };
+        Ok(())
+    }
+
+    /// Enqueue a clone of the writable `Data`, parking the current thread while the queue is full,
+    /// until a reader dequeues an entry.
+    #[cfg(feature = "std")]
+    pub fn flush_blocking(&mut self, #[allow(unused)] channel_key: &ChannelKey) {
+        let channel = self.channel.as_ptr();
+        let mut queue = unsafe { Queue::lock(self.channel) };
+        while queue.len() == self.capacity {
+            queue = unsafe { Queue::dequeued(self.channel) }
+                .wait(queue)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+        queue.push_back(unsafe { (*channel).writable.clone() });
+        drop(queue);
+        unsafe { (*channel).dirty = false };
+    }
+}
+
+impl<Data> Queue<Data> {
+    /// Lock the queue of the given channel.
+    /// This only borrows the queue, and not the writable `Data`, which may be accessed concurrently.
+    ///
+    /// # Safety
+    ///
+    /// The channel must not be destroyed while the guard exists.
+    unsafe fn lock<'a>(channel: NonNull<Self>) -> MutexGuard<'a, VecDeque<Data>> {
+        let queue: &'a Mutex<VecDeque<Data>> = &*ptr::addr_of!((*channel.as_ptr()).queue);
+        queue.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// The condition variable of the given channel that is signaled whenever an entry is dequeued.
+    ///
+    /// # Safety
+    ///
+    /// The channel must not be destroyed while the reference exists.
+    #[cfg(feature = "std")]
+    unsafe fn dequeued<'a>(channel: NonNull<Self>) -> &'a Condvar {
+        &*ptr::addr_of!((*channel.as_ptr()).dequeued)
+    }
+}
+
+impl<Data> QueuedDataPointer<Data> {
+    /// Dequeue the oldest flushed `Data`, or return `None` if the queue is empty.
+    pub fn try_recv(&self) -> Option<Data> {
+        let data = unsafe { Queue::lock(self.queue) }.pop_front();
+        #[cfg(feature = "std")]
+        if data.is_some() {
+            unsafe { Queue::dequeued(self.queue) }.notify_all();
+        }
+        data
+    }
+
+    /// The number of entries in the queue.
+    pub fn len(&self) -> usize {
+        unsafe { Queue::lock(self.queue) }.len()
+    }
+
+    /// Returns true if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<Data> Clone for QueuedDataPointer<Data> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Data> Copy for QueuedDataPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Send for QueuedChannel<Data> {}
+unsafe impl<Data: Send + Sync> Send for QueuedDataPointer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for QueuedChannel<Data> {}
+unsafe impl<Data: Send + Sync> Sync for QueuedDataPointer<Data> {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    use crate::{
+        queued::{OverflowPolicy, QueueFullError, QueuedChannel},
+        MasterKey,
+    };
+
+    #[test]
+    fn test_drop_oldest() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel, queued_data_pointer, mut writable_data_pointer) =
+            QueuedChannel::create(2, OverflowPolicy::DropOldest, 0);
+
+        for value in 1..=3 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = value;
+            assert_eq!(channel.flush(&master_key.get_channel_key()), Ok(()));
+        }
+
+        assert_eq!(queued_data_pointer.len(), 2);
+        assert_eq!(queued_data_pointer.try_recv(), Some(2));
+        assert_eq!(queued_data_pointer.try_recv(), Some(3));
+        assert_eq!(queued_data_pointer.try_recv(), None);
+
+        let (queue, writable) = channel.destroy([queued_data_pointer], writable_data_pointer);
+        assert!(queue.is_empty());
+        assert_eq!(writable, 3);
+    }
+
+    #[test]
+    fn test_remaining_capacity() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel, queued_data_pointer, writable_data_pointer) =
+            QueuedChannel::create(2, OverflowPolicy::Reject, 0);
+        assert_eq!(channel.remaining_capacity(), 2);
+
+        channel.flush(&master_key.get_channel_key()).unwrap();
+        assert_eq!(channel.remaining_capacity(), 1);
+        assert!(!channel.is_full());
+        channel.flush(&master_key.get_channel_key()).unwrap();
+        assert!(channel.is_full());
+
+        queued_data_pointer.try_recv();
+        assert_eq!(channel.remaining_capacity(), 1);
+
+        channel.destroy([queued_data_pointer], writable_data_pointer);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_flush_blocking() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel, queued_data_pointer, mut writable_data_pointer) =
+            QueuedChannel::create(1, OverflowPolicy::Reject, 0);
+        channel.flush(&master_key.get_channel_key()).unwrap();
+        assert!(channel.is_full());
+
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 1;
+        let flushed = AtomicBool::new(false);
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                channel.flush_blocking(&master_key.get_channel_key());
+                flushed.store(true, Ordering::SeqCst);
+            });
+
+            thread::sleep(Duration::from_millis(50));
+            assert!(!flushed.load(Ordering::SeqCst));
+            assert_eq!(queued_data_pointer.try_recv(), Some(0));
+        });
+        assert!(flushed.load(Ordering::SeqCst));
+        assert_eq!(queued_data_pointer.try_recv(), Some(1));
+
+        channel.destroy([queued_data_pointer], writable_data_pointer);
+    }
+
+    #[test]
+    fn test_reject() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel, queued_data_pointer, mut writable_data_pointer) =
+            QueuedChannel::create(2, OverflowPolicy::Reject, 0);
+
+        for value in 1..=2 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = value;
+            assert_eq!(channel.flush(&master_key.get_channel_key()), Ok(()));
+        }
+        *writable_data_pointer.get_mut(&master_key.get_data_key()) = 3;
+        assert_eq!(
+            channel.flush(&master_key.get_channel_key()),
+            Err(QueueFullError { capacity: 2 })
+        );
+
+        assert_eq!(queued_data_pointer.try_recv(), Some(1));
+        assert_eq!(channel.flush(&master_key.get_channel_key()), Ok(()));
+
+        let (queue, writable) = channel.destroy([queued_data_pointer], writable_data_pointer);
+        assert_eq!(queue, [2, 3]);
+        assert_eq!(writable, 3);
+    }
+}
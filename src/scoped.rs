@@ -0,0 +1,333 @@
+//! Directed two-phase channels with their own independent phases.
+//! Keys obtained via [`MasterKey::get_data_key`] and [`MasterKey::get_channel_key`] gate all channels at once,
+//! so holding a data key blocks flushing any channel.
+//! Channels created with a [`ChannelId`] instead are gated only by the keys for this id,
+//! so one thread can flush one channel while another thread reads a different channel.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey, KeyCount, MasterKey,
+};
+
+/// The phase state of a channel id while a [`ScopedChannelKey`] exists.
+const FLUSHING: usize = usize::MAX;
+
+/// The id of a scoped channel, which defines the phases of all channels created with it.
+/// Ids are compared by identity, so clones of an id refer to the same phases.
+///
+/// See [`MasterKey::get_data_key_for`] and [`MasterKey::get_channel_key_for`] for more info.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelId {
+    /// The number of existing [`ScopedDataKey`]s, or [`FLUSHING`] if a [`ScopedChannelKey`] exists.
+    phase: Arc<AtomicUsize>,
+}
+
+/// The key used for accessing the data pointers of scoped channels with a certain [`ChannelId`].
+/// Any number of them can exist simultaneously for the same id, but only if there is no channel key for this id.
+#[derive(Debug)]
+pub struct ScopedDataKey<'id> {
+    id: &'id ChannelId,
+    _count: KeyCount<'id>,
+}
+
+/// The key used for accessing the channel pointers of scoped channels with a certain [`ChannelId`].
+/// Only one can exist at any point for the same id, and only if there is no data key for this id.
+#[derive(Debug)]
+pub struct ScopedChannelKey<'id> {
+    id: &'id ChannelId,
+    _count: KeyCount<'id>,
+}
+
+/// A pointer to a scoped directed channel.
+/// It can only be accessed using a [ScopedChannelKey] with the id of the channel.
+///
+/// This type should always be destroyed via the [ScopedChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct ScopedChannelPointer<Data> {
+    channel: DirectedChannelPointer<Data>,
+    id: ChannelId,
+}
+
+/// A pointer to the read-only data field in a scoped directed channel.
+/// It can only be accessed using a [ScopedDataKey] with the id of the channel.
+///
+/// This type should always be destroyed via the [ScopedChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct ScopedReadOnlyDataPointer<Data> {
+    data: ReadOnlyDataPointer<Data>,
+    id: ChannelId,
+}
+
+/// A pointer to the writable data field in a scoped directed channel.
+/// It can only be accessed using a [ScopedDataKey] with the id of the channel.
+///
+/// This type should always be destroyed via the [ScopedChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct ScopedWritableDataPointer<Data> {
+    data: WritableDataPointer<Data>,
+    id: ChannelId,
+}
+
+impl ChannelId {
+    /// Create a new channel id with its own phases.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is(&self, other: &ChannelId) -> bool {
+        Arc::ptr_eq(&self.phase, &other.phase)
+    }
+}
+
+impl MasterKey {
+    /// Get a data key for the channels with the given id.
+    /// Unlike [`MasterKey::get_data_key`], this only borrows the master key immutably,
+    /// so keys for different ids can exist at the same time.
+    ///
+    /// **Panics** if there is a channel key for the given id,
+    /// or if this would exceed the limit of outstanding scoped keys, see [`MasterKey::create_with_scoped_key_limit`].
+    pub fn get_data_key_for<'id>(&'id self, id: &'id ChannelId) -> ScopedDataKey<'id> {
+        let count = self.count_key();
+        let mut phase = id.phase.load(Ordering::Relaxed);
+        loop {
+            assert_ne!(
+                phase, FLUSHING,
+                "cannot get a data key while there is a channel key for the same id"
+            );
+            match id.phase.compare_exchange_weak(
+                phase,
+                phase + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return ScopedDataKey { id, _count: count },
+                Err(current) => phase = current,
+            }
+        }
+    }
+
+    /// Get a channel key for the channels with the given id.
+    /// Unlike [`MasterKey::get_channel_key`], this only borrows the master key immutably,
+    /// so keys for different ids can exist at the same time.
+    ///
+    /// **Panics** if there is a data key or another channel key for the given id,
+    /// or if this would exceed the limit of outstanding scoped keys, see [`MasterKey::create_with_scoped_key_limit`].
+    pub fn get_channel_key_for<'id>(&'id self, id: &'id ChannelId) -> ScopedChannelKey<'id> {
+        let count = self.count_key();
+        assert!(
+            id.phase
+                .compare_exchange(0, FLUSHING, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok(),
+            "cannot get a channel key while there is another key for the same id"
+        );
+        ScopedChannelKey { id, _count: count }
+    }
+}
+
+impl<'id> ScopedDataKey<'id> {
+    fn data_key(&self, id: &ChannelId) -> DataKey<'_> {
+        assert!(self.id.is(id), "the key must have the id of the channel");
+        DataKey {
+            scope: Default::default(),
+        }
+    }
+}
+
+impl<'id> ScopedChannelKey<'id> {
+    fn channel_key(&self, id: &ChannelId) -> ChannelKey<'_> {
+        assert!(self.id.is(id), "the key must have the id of the channel");
+        ChannelKey {
+            scope: Default::default(),
+        }
+    }
+}
+
+impl<'id> Drop for ScopedDataKey<'id> {
+    fn drop(&mut self) {
+        self.id.phase.fetch_sub(1, Ordering::Release);
+    }
+}
+
+impl<'id> Drop for ScopedChannelKey<'id> {
+    fn drop(&mut self) {
+        self.id.phase.store(0, Ordering::Release);
+    }
+}
+
+impl<Data> DirectedChannel<Data> {
+    /// Create a directed channel whose phases are defined by the given id, and hand out three pointers to it.
+    /// The pointers can only be accessed with the keys for this id, see [`MasterKey::get_data_key_for`] and [`MasterKey::get_channel_key_for`].
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    pub fn create_scoped(
+        id: &ChannelId,
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        ScopedChannelPointer<Data>,
+        ScopedReadOnlyDataPointer<Data>,
+        ScopedWritableDataPointer<Data>,
+    ) {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            Self::create(read_only, writable);
+        (
+            ScopedChannelPointer {
+                channel: channel_pointer,
+                id: id.clone(),
+            },
+            ScopedReadOnlyDataPointer {
+                data: read_only_data_pointer,
+                id: id.clone(),
+            },
+            ScopedWritableDataPointer {
+                data: writable_data_pointer,
+                id: id.clone(),
+            },
+        )
+    }
+}
+
+impl<Data: Clone> ScopedChannelPointer<Data> {
+    /// Clone the writable `Data` into the read-only `Data`.
+    ///
+    /// See [`DirectedChannel::flush`].
+    ///
+    /// **Panics** if the key does not have the id of this channel.
+    pub fn flush(&mut self, channel_key: &ScopedChannelKey) {
+        self.channel.flush(&channel_key.channel_key(&self.id));
+    }
+}
+
+impl<Data> ScopedChannelPointer<Data> {
+    /// The id of this channel.
+    pub fn id(&self) -> &ChannelId {
+        &self.id
+    }
+
+    /// Destroys the scoped channel linked with the given pointers (see [DirectedChannel::create_scoped]).
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ScopedReadOnlyDataPointer<Data>>,
+        writable_data_pointer: ScopedWritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        self.channel.destroy(
+            read_only_data_pointers
+                .into_iter()
+                .map(|read_only_data_pointer| read_only_data_pointer.data),
+            writable_data_pointer.data,
+        )
+    }
+}
+
+impl<Data> ScopedReadOnlyDataPointer<Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer.
+    ///
+    /// **Panics** if the key does not have the id of this channel.
+    pub fn get<'a>(&'a self, data_key: &'a ScopedDataKey) -> &'a Data {
+        self.data.get(&data_key.data_key(&self.id))
+    }
+}
+
+impl<Data> ScopedWritableDataPointer<Data> {
+    /// Get a reference to the `Data` field pointed to by this pointer.
+    ///
+    /// **Panics** if the key does not have the id of this channel.
+    pub fn get<'a>(&'a self, data_key: &'a ScopedDataKey) -> &'a Data {
+        self.data.get(&data_key.data_key(&self.id))
+    }
+
+    /// Get a mutable reference to the `Data` field pointed to by this pointer.
+    ///
+    /// **Panics** if the key does not have the id of this channel.
+    pub fn get_mut<'a>(&'a mut self, data_key: &'a ScopedDataKey) -> &'a mut Data {
+        self.data.get_mut(&data_key.data_key(&self.id))
+    }
+}
+
+impl<Data> Clone for ScopedReadOnlyDataPointer<Data> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            id: self.id.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{
+        directed::DirectedChannel,
+        scoped::{ChannelId, ScopedChannelPointer},
+        MasterKey,
+    };
+
+    #[test]
+    fn test_independent_phases() {
+        let master_key = unsafe { MasterKey::create_unlimited() };
+        let id1 = ChannelId::new();
+        let id2 = ChannelId::new();
+        let (channel_pointer1, read_only_data_pointer1, writable_data_pointer1) =
+            DirectedChannel::create_scoped(&id1, 1, 1);
+        let (mut channel_pointer2, read_only_data_pointer2, mut writable_data_pointer2) =
+            DirectedChannel::create_scoped(&id2, 0, 0);
+
+        let data_key1 = master_key.get_data_key_for(&id1);
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    assert_eq!(*read_only_data_pointer1.get(&data_key1), 1);
+                }
+            });
+            scope.spawn(|| {
+                for i in 1..=100 {
+                    *writable_data_pointer2.get_mut(&master_key.get_data_key_for(&id2)) = i;
+                    channel_pointer2.flush(&master_key.get_channel_key_for(&id2));
+                }
+            });
+        });
+        drop(data_key1);
+
+        assert_eq!(
+            *read_only_data_pointer2.get(&master_key.get_data_key_for(&id2)),
+            100
+        );
+        ScopedChannelPointer::destroy(
+            channel_pointer1,
+            [read_only_data_pointer1],
+            writable_data_pointer1,
+        );
+        channel_pointer2.destroy([read_only_data_pointer2], writable_data_pointer2);
+    }
+
+    #[test]
+    #[should_panic(expected = "while there is another key")]
+    fn test_channel_key_while_data_key() {
+        let master_key = unsafe { MasterKey::create_unlimited() };
+        let id = ChannelId::new();
+        let _data_key = master_key.get_data_key_for(&id);
+        let _channel_key = master_key.get_channel_key_for(&id);
+    }
+
+    #[test]
+    #[should_panic(expected = "must have the id of the channel")]
+    fn test_key_with_wrong_id() {
+        let master_key = unsafe { MasterKey::create_unlimited() };
+        let id1 = ChannelId::new();
+        let id2 = ChannelId::new();
+        let (_channel_pointer, read_only_data_pointer, _writable_data_pointer) =
+            DirectedChannel::create_scoped(&id1, 0, 0);
+        read_only_data_pointer.get(&master_key.get_data_key_for(&id2));
+    }
+}
@@ -0,0 +1,212 @@
+//! A sequence lock for a single writer and any number of readers of `Copy` data.
+//! Unlike the two-phase channels of this crate, it needs no keys:
+//! the writer marks its writes with a sequence counter, and readers retry if the counter changed while they were reading.
+//! This provides lock-free reads for plain old data without any phase coordination.
+
+use std::{cell::UnsafeCell, mem::MaybeUninit, ptr, sync::atomic::Ordering};
+
+use crate::ordering::{fence, hint, Arc, AtomicU64, MemoryOrdering};
+
+/// A `Data` guarded by a sequence counter, shared by a [SeqlockWriter] and any number of [SeqlockReader]s.
+///
+/// See [SeqlockChannel::create] for more info.
+#[derive(Debug)]
+pub struct SeqlockChannel<Data> {
+    data: UnsafeCell<Data>,
+    /// Odd while the writer is writing, and even otherwise.
+    sequence: AtomicU64,
+    ordering: MemoryOrdering,
+}
+
+/// The writing half of a seqlock channel.
+/// It replaces the `Data` via [SeqlockWriter::write].
+#[derive(Debug)]
+pub struct SeqlockWriter<Data> {
+    channel: Arc<SeqlockChannel<Data>>,
+}
+
+/// A reading half of a seqlock channel.
+/// It loads a copy of the `Data` that was written last via [SeqlockReader::load].
+///
+/// Readers can be cloned to read from multiple threads.
+#[derive(Debug)]
+pub struct SeqlockReader<Data> {
+    channel: Arc<SeqlockChannel<Data>>,
+}
+
+impl<Data: Copy> SeqlockChannel<Data> {
+    /// Create a seqlock channel and hand out its writer and a reader.
+    /// The reader loads the given `Data` until the writer writes a new one.
+    ///
+    /// The channel uses the default [`MemoryOrdering`], see [`SeqlockChannel::create_with_ordering`].
+    pub fn create(data: Data) -> (SeqlockWriter<Data>, SeqlockReader<Data>) {
+        Self::create_with_ordering(data, MemoryOrdering::default())
+    }
+
+    /// Create a seqlock channel whose atomic operations use the given [`MemoryOrdering`].
+    ///
+    /// See [`SeqlockChannel::create`] for more info.
+    pub fn create_with_ordering(
+        data: Data,
+        ordering: MemoryOrdering,
+    ) -> (SeqlockWriter<Data>, SeqlockReader<Data>) {
+        let channel = Arc::new(Self {
+            data: UnsafeCell::new(data),
+            sequence: AtomicU64::new(0),
+            ordering,
+        });
+        (
+            SeqlockWriter {
+                channel: channel.clone(),
+            },
+            SeqlockReader { channel },
+        )
+    }
+
+    /// Destroys the seqlock channel linked with the writer and the readers (see [SeqlockChannel::create]).
+    /// Returns the `Data` that was written last.
+    ///
+    /// **Panics** if not all readers belong to the channel of the writer, or if not all readers of the channel are passed.
+    pub fn destroy(
+        writer: SeqlockWriter<Data>,
+        readers: impl IntoIterator<Item = SeqlockReader<Data>>,
+    ) -> Data {
+        for reader in readers {
+            assert!(Arc::ptr_eq(&writer.channel, &reader.channel));
+        }
+        match Arc::try_unwrap(writer.channel) {
+            Ok(channel) => channel.data.into_inner(),
+            Err(_) => panic!("not all readers of the seqlock channel were passed"),
+        }
+    }
+}
+
+impl<Data: Copy> SeqlockWriter<Data> {
+    /// Replace the `Data` of the channel.
+    /// Readers that load concurrently retry until the write is complete.
+    pub fn write(&mut self, data: Data) {
+        let channel = &self.channel;
+        let ordering = channel.ordering;
+        // Only the writer modifies the sequence, so it can load it without synchronisation.
+        let sequence = channel.sequence.load(Ordering::Relaxed);
+        channel.sequence.store(sequence + 1, Ordering::Relaxed);
+        // Even sequentially consistent stores do not prevent the `Data` from being written before the odd sequence.
+        fence(Ordering::Release);
+        unsafe { ptr::write_volatile(channel.data.get(), data) };
+        ordering.release_fence();
+        channel.sequence.store(sequence + 2, ordering.store());
+    }
+}
+
+impl<Data: Copy> SeqlockReader<Data> {
+    /// Load a copy of the `Data` that was written last.
+    /// This never blocks the writer, but retries as long as the writer writes concurrently.
+    pub fn load(&self) -> Data {
+        let channel = &self.channel;
+        let ordering = channel.ordering;
+        loop {
+            let sequence = channel.sequence.load(ordering.load());
+            if sequence % 2 == 1 {
+                hint::spin_loop();
+                continue;
+            }
+            ordering.acquire_fence();
+            // The copy may be torn if the writer writes concurrently, but then the sequence changed and it is discarded.
+            // Until then, it is kept as `MaybeUninit`, since a torn copy may not be a valid `Data`.
+            let data =
+                unsafe { ptr::read_volatile(channel.data.get() as *const MaybeUninit<Data>) };
+            // Even sequentially consistent loads do not prevent the `Data` from being read after the second load of the sequence.
+            fence(Ordering::Acquire);
+            if channel.sequence.load(Ordering::Relaxed) == sequence {
+                return unsafe { data.assume_init() };
+            }
+        }
+    }
+}
+
+impl<Data> Clone for SeqlockReader<Data> {
+    fn clone(&self) -> Self {
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for SeqlockWriter<Data> {}
+unsafe impl<Data: Send + Sync> Send for SeqlockReader<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for SeqlockWriter<Data> {}
+unsafe impl<Data: Send + Sync> Sync for SeqlockReader<Data> {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(loom))]
+    use std::thread;
+
+    use crate::{ordering::MemoryOrdering, seqlock::SeqlockChannel};
+
+    #[cfg(not(loom))]
+    #[test]
+    fn test_no_tearing() {
+        for ordering in [
+            MemoryOrdering::Relaxed,
+            MemoryOrdering::AcquireRelease,
+            MemoryOrdering::SeqCst,
+        ] {
+            let (mut writer, reader) = SeqlockChannel::create_with_ordering([0u64; 16], ordering);
+            let readers: Vec<_> = (0..2).map(|_| reader.clone()).collect();
+
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    for i in 1..=100_000 {
+                        writer.write([i; 16]);
+                    }
+                });
+                for reader in &readers {
+                    scope.spawn(move || {
+                        let mut last = 0;
+                        while last < 100_000 {
+                            let data = reader.load();
+                            assert!(data.iter().all(|value| *value == data[0]), "torn read");
+                            assert!(data[0] >= last);
+                            last = data[0];
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(
+                SeqlockChannel::destroy(writer, readers.into_iter().chain([reader])),
+                [100_000; 16]
+            );
+        }
+    }
+
+    /// Build with `cargo rustc --lib --profile test -- --cfg loom` and run the resulting test binary.
+    /// Setting `--cfg loom` via `RUSTFLAGS` does not work, since it changes the dependencies as well.
+    #[cfg(loom)]
+    #[test]
+    fn test_loom_acquire_release() {
+        loom::model(|| {
+            let (mut writer, reader) =
+                SeqlockChannel::create_with_ordering([0u64; 2], MemoryOrdering::AcquireRelease);
+            let reader2 = reader.clone();
+            let thread = loom::thread::spawn(move || {
+                let mut last = 0;
+                for _ in 0..2 {
+                    let data = reader2.load();
+                    assert_eq!(data[0], data[1], "torn read");
+                    assert!(data[0] >= last);
+                    last = data[0];
+                }
+                reader2
+            });
+
+            writer.write([1; 2]);
+            writer.write([2; 2]);
+            let reader2 = thread.join().unwrap();
+            assert_eq!(reader.load(), [2; 2]);
+            assert_eq!(SeqlockChannel::destroy(writer, [reader, reader2]), [2; 2]);
+        });
+    }
+}
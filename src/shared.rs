@@ -0,0 +1,203 @@
+//! A directed two-phase channel that is destroyed automatically when its last handle is dropped,
+//! as an alternative to passing every pointer back to [`DirectedChannel::destroy`].
+
+use std::{cell::UnsafeCell, mem::ManuallyDrop, sync::Arc};
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey,
+};
+
+/// A directed channel shared by its handles via an [`Arc`].
+/// The `Arc` counts the outstanding handles, and the channel is destroyed when the last of them is dropped.
+///
+/// See [SharedDirectedChannel::create] for more info.
+#[derive(Debug)]
+pub struct SharedDirectedChannel<Data> {
+    channel_pointer: UnsafeCell<ManuallyDrop<DirectedChannelPointer<Data>>>,
+    read_only_data_pointer: ReadOnlyDataPointer<Data>,
+    writable_data_pointer: UnsafeCell<ManuallyDrop<WritableDataPointer<Data>>>,
+}
+
+/// The handle of a shared channel used to flush it.
+/// It can only be accessed using a [ChannelKey].
+#[derive(Debug)]
+#[must_use]
+pub struct SharedChannelPointer<Data> {
+    channel: Arc<SharedDirectedChannel<Data>>,
+}
+
+/// A handle of a shared channel used to read from it.
+/// It can only be accessed using a [DataKey].
+///
+/// Readers can be cloned to read from multiple threads.
+#[derive(Debug)]
+pub struct SharedReader<Data> {
+    channel: Arc<SharedDirectedChannel<Data>>,
+}
+
+/// The handle of a shared channel used to write to it.
+/// It can only be accessed using a [DataKey].
+#[derive(Debug)]
+pub struct SharedWriter<Data> {
+    channel: Arc<SharedDirectedChannel<Data>>,
+}
+
+impl<Data> SharedDirectedChannel<Data> {
+    /// Create a shared channel and hand out three handles to it.
+    /// One [SharedChannelPointer] used to flush the channel,
+    /// one [SharedReader] used to read from the channel, and
+    /// one [SharedWriter] used to write to the channel.
+    ///
+    /// The handles can be dropped in any order, and the channel is destroyed together with the last of them.
+    pub fn create(
+        read_only: Data,
+        writable: Data,
+    ) -> (
+        SharedChannelPointer<Data>,
+        SharedReader<Data>,
+        SharedWriter<Data>,
+    ) {
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(read_only, writable);
+        let channel = Arc::new(Self {
+            channel_pointer: UnsafeCell::new(ManuallyDrop::new(channel_pointer)),
+            read_only_data_pointer,
+            writable_data_pointer: UnsafeCell::new(ManuallyDrop::new(writable_data_pointer)),
+        });
+        (
+            SharedChannelPointer {
+                channel: channel.clone(),
+            },
+            SharedReader {
+                channel: channel.clone(),
+            },
+            SharedWriter { channel },
+        )
+    }
+}
+
+impl<Data> Drop for SharedDirectedChannel<Data> {
+    fn drop(&mut self) {
+        // Only called once the last handle is dropped, so no handle can access the pointers anymore.
+        let channel_pointer = unsafe { ManuallyDrop::take(self.channel_pointer.get_mut()) };
+        let writable_data_pointer =
+            unsafe { ManuallyDrop::take(self.writable_data_pointer.get_mut()) };
+        channel_pointer.destroy_single(self.read_only_data_pointer, writable_data_pointer);
+    }
+}
+
+impl<Data: Clone> SharedChannelPointer<Data> {
+    /// Shorthand for [DirectedChannelPointer::flush].
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        // There is only one channel handle, and it is borrowed mutably.
+        unsafe { (*self.channel.channel_pointer.get()).flush(channel_key) };
+    }
+}
+
+impl<Data> SharedChannelPointer<Data> {
+    /// Shorthand for [DirectedChannelPointer::flush_count].
+    pub fn flush_count(&self) -> u64 {
+        unsafe { (*self.channel.channel_pointer.get()).flush_count() }
+    }
+}
+
+impl<Data> SharedReader<Data> {
+    /// Shorthand for [ReadOnlyDataPointer::get].
+    pub fn get(&self, data_key: &DataKey) -> &Data {
+        self.channel.read_only_data_pointer.get(data_key)
+    }
+
+    /// Shorthand for [ReadOnlyDataPointer::version].
+    pub fn version(&self, data_key: &DataKey) -> u64 {
+        self.channel.read_only_data_pointer.version(data_key)
+    }
+}
+
+impl<Data> SharedWriter<Data> {
+    /// Shorthand for [WritableDataPointer::get].
+    pub fn get(&self, data_key: &DataKey) -> &Data {
+        unsafe { (*self.channel.writable_data_pointer.get()).get(data_key) }
+    }
+
+    /// Shorthand for [WritableDataPointer::get_mut].
+    pub fn get_mut(&mut self, data_key: &DataKey) -> &mut Data {
+        // There is only one writer, and it is borrowed mutably.
+        unsafe { (*self.channel.writable_data_pointer.get()).get_mut(data_key) }
+    }
+}
+
+impl<Data> Clone for SharedReader<Data> {
+    fn clone(&self) -> Self {
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for SharedChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for SharedReader<Data> {}
+unsafe impl<Data: Send + Sync> Send for SharedWriter<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for SharedChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for SharedReader<Data> {}
+unsafe impl<Data: Send + Sync> Sync for SharedWriter<Data> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{shared::SharedDirectedChannel, MasterKey};
+
+    #[test]
+    fn test_read_write_flush() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, reader, mut writer) = SharedDirectedChannel::create(0, 0);
+        *writer.get_mut(&master_key.get_data_key()) = 5;
+        channel_pointer.flush(&master_key.get_channel_key());
+
+        let data_key = master_key.get_data_key();
+        let reader2 = reader.clone();
+        assert_eq!(*reader.get(&data_key), 5);
+        assert_eq!(*reader2.get(&data_key), 5);
+        assert_eq!(reader2.version(&data_key), 1);
+        assert_eq!(*writer.get(&data_key), 5);
+        assert_eq!(channel_pointer.flush_count(), 1);
+    }
+
+    /// Miri checks that the channel is freed exactly once via `cargo +nightly miri test shared`.
+    #[test]
+    fn test_drop_in_any_order() {
+        let orders = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        for order in orders {
+            let tracker = Arc::new(());
+            let (channel_pointer, reader, writer) =
+                SharedDirectedChannel::create(tracker.clone(), tracker.clone());
+            let mut channel_pointer = Some(channel_pointer);
+            let mut reader = Some(reader);
+            let mut writer = Some(writer);
+
+            for (dropped, handle) in order.into_iter().enumerate() {
+                assert_eq!(
+                    Arc::strong_count(&tracker),
+                    3,
+                    "freed after {} drops",
+                    dropped
+                );
+                match handle {
+                    0 => drop(channel_pointer.take()),
+                    1 => drop(reader.take()),
+                    _ => drop(writer.take()),
+                }
+            }
+            assert_eq!(Arc::strong_count(&tracker), 1);
+        }
+    }
+}
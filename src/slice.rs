@@ -0,0 +1,218 @@
+//! A slice two-phase channel.
+//! This is a wrapper around a directed two-phase channel of `Vec`s,
+//! which can flush single elements or ranges of elements instead of the whole `Vec`.
+
+use std::{ops::Range, ptr};
+
+use crate::{
+    directed::{DirectedChannel, IDirectedChannel, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey, DataKey,
+};
+
+/// A slice channel used for communication between threads.
+/// It holds a directed channel of `Vec<T>`, and allows to flush only parts of the `Vec`.
+///
+/// The writer should not change the length of the writable `Vec`, since the partial flushes require both `Vec`s to have the same length.
+///
+/// See [`DirectedChannel`](crate::directed::DirectedChannel) for more info.
+#[derive(Debug)]
+pub struct SliceChannel<T> {
+    channel: DirectedChannel<Vec<T>>,
+}
+
+/// A pointer to a slice channel.
+/// It can only be accessed using a [ChannelKey].
+///
+/// This type should always be destroyed via the [SliceChannel::destroy] or [SliceChannelPointer::destroy] method to ensure soundness (at runtime).
+#[derive(Debug)]
+#[must_use]
+pub struct SliceChannelPointer<T> {
+    channel: Box<SliceChannel<T>>,
+}
+
+impl<T> SliceChannel<T> {
+    /// Create a slice channel and hand out three pointers to it.
+    /// One [SliceChannelPointer] used to flush (copy) the content of the writable `Vec` into the read-only `Vec`,
+    /// one [ReadOnlyDataPointer] used to read from the slice channel, and
+    /// one [WritableDataPointer] used to write to the slice channel.
+    ///
+    /// **Panics** if the two `Vec`s have different lengths.
+    pub fn create(
+        read_only: Vec<T>,
+        writable: Vec<T>,
+    ) -> (
+        SliceChannelPointer<T>,
+        ReadOnlyDataPointer<Vec<T>>,
+        WritableDataPointer<Vec<T>>,
+    ) {
+        assert_eq!(
+            read_only.len(),
+            writable.len(),
+            "the read-only and the writable Vec of a slice channel must have the same length"
+        );
+        let mut channel_pointer = SliceChannelPointer {
+            channel: Box::new(SliceChannel {
+                channel: DirectedChannel::new(read_only, writable),
+            }),
+        };
+        let read_only_data_pointer = channel_pointer.channel.channel.read_only_data_pointer();
+        let writable_data_pointer = channel_pointer.channel.channel.writable_data_pointer();
+        (
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Destroys the slice channel linked with the given pointers (see [SliceChannel::create]).
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        channel_pointer: SliceChannelPointer<T>,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Vec<T>>>,
+        writable_data_pointer: WritableDataPointer<Vec<T>>,
+    ) -> (Vec<T>, Vec<T>) {
+        let SliceChannelPointer { channel } = channel_pointer;
+        let WritableDataPointer { data: writable, .. } = writable_data_pointer;
+        assert!(ptr::eq(&channel.channel.writable.0, writable.as_ptr()));
+
+        for read_only_data_pointer in read_only_data_pointers {
+            let ReadOnlyDataPointer {
+                data: read_only, ..
+            } = read_only_data_pointer;
+            assert!(ptr::eq(&channel.channel.read_only.0, read_only.as_ptr()));
+        }
+
+        let SliceChannel { channel } = *channel;
+        (channel.read_only.0, channel.writable.0)
+    }
+}
+
+impl<T: Clone> SliceChannelPointer<T> {
+    /// Clone the whole writable `Vec` into the read-only `Vec`.
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        self.channel.channel.flush(channel_key);
+    }
+
+    /// Clone the element at index `i` of the writable `Vec` into the read-only `Vec`.
+    ///
+    /// **Panics** if the index is out of bounds, or if the writable `Vec` changed its length.
+    pub fn flush_index(&mut self, channel_key: &ChannelKey, i: usize) {
+        self.flush_range(channel_key, i..i + 1);
+    }
+
+    /// Clone the elements in the given range of the writable `Vec` into the read-only `Vec`.
+    ///
+    /// **Panics** if the range is out of bounds, or if the writable `Vec` changed its length.
+    pub fn flush_range(&mut self, #[allow(unused)] channel_key: &ChannelKey, range: Range<usize>) {
+        let channel: &mut DirectedChannel<Vec<T>> = &mut self.channel.channel;
+        channel.assert_not_frozen();
+        assert_eq!(
+            channel.read_only.len(),
+            channel.writable.len(),
+            "the writable Vec of a slice channel must not change its length"
+        );
+        // Clone before assigning, such that the read-only `Vec` stays unchanged if a clone panics.
+        let clone = channel.writable[range.clone()].to_vec();
+        for (read_only, clone) in channel.read_only[range].iter_mut().zip(clone) {
+            *read_only = clone;
+        }
+        channel.flushed_partially();
+    }
+}
+
+impl<T> SliceChannelPointer<T> {
+    /// Shorthand for [SliceChannel::destroy].
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Vec<T>>>,
+        writable_data_pointer: WritableDataPointer<Vec<T>>,
+    ) -> (Vec<T>, Vec<T>) {
+        SliceChannel::destroy(self, read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+impl<T> ReadOnlyDataPointer<Vec<T>> {
+    /// Get a reference to the element at index `i` of the `Vec` pointed to by this pointer.
+    ///
+    /// **Panics** if the index is out of bounds.
+    pub fn get_index(&self, data_key: &DataKey, i: usize) -> &T {
+        &self.get(data_key)[i]
+    }
+}
+
+unsafe impl<T: Send + Sync> Send for SliceChannelPointer<T> {}
+
+unsafe impl<T: Send + Sync> Sync for SliceChannelPointer<T> {}
+
+impl<T: Clone + Send + Sync> IDirectedChannel for SliceChannelPointer<T> {
+    fn flush(&mut self, channel_key: &ChannelKey) {
+        SliceChannelPointer::flush(self, channel_key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{slice::SliceChannel, MasterKey};
+
+    #[test]
+    fn test() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            SliceChannel::create(vec![0; 8], vec![0; 8]);
+
+        let data_key = master_key.get_data_key();
+        for value in writable_data_pointer.get_mut(&data_key).iter_mut() {
+            *value = 1;
+        }
+        writable_data_pointer.get_mut(&data_key)[2] = 2;
+        writable_data_pointer.get_mut(&data_key)[5] = 5;
+
+        let channel_key = data_key.into_channel_key();
+        channel_pointer.flush_index(&channel_key, 2);
+        channel_pointer.flush_index(&channel_key, 5);
+
+        let data_key = channel_key.into_data_key();
+        assert_eq!(*read_only_data_pointer.get_index(&data_key, 2), 2);
+        assert_eq!(*read_only_data_pointer.get_index(&data_key, 5), 5);
+        assert_eq!(
+            *read_only_data_pointer.get(&data_key),
+            [0, 0, 2, 0, 0, 5, 0, 0]
+        );
+        assert_eq!(read_only_data_pointer.version(&data_key), 2);
+
+        let channel_key = data_key.into_channel_key();
+        channel_pointer.flush_range(&channel_key, 0..4);
+        let data_key = channel_key.into_data_key();
+        assert_eq!(
+            *read_only_data_pointer.get(&data_key),
+            [1, 1, 2, 1, 0, 5, 0, 0]
+        );
+
+        let (read_only_data, writable_data) = SliceChannel::destroy(
+            channel_pointer,
+            [read_only_data_pointer],
+            writable_data_pointer,
+        );
+        assert_eq!(read_only_data, [1, 1, 2, 1, 0, 5, 0, 0]);
+        assert_eq!(writable_data, [1, 1, 2, 1, 1, 5, 1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must have the same length")]
+    fn test_create_with_different_lengths() {
+        let _ = SliceChannel::create(vec![0; 2], vec![0; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not change its length")]
+    fn test_flush_after_length_change() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, _read_only_data_pointer, mut writable_data_pointer) =
+            SliceChannel::create(vec![0; 2], vec![0; 2]);
+        writable_data_pointer
+            .get_mut(&master_key.get_data_key())
+            .push(0);
+        channel_pointer.flush_index(&master_key.get_channel_key(), 0);
+    }
+}
@@ -0,0 +1,149 @@
+//! Streams of flush notifications for readers in async contexts.
+//! This module is only available with the `futures` feature.
+
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use futures_core::Stream;
+
+use crate::directed::DirectedChannelPointer;
+
+/// Wakes the [`FlushStream`]s of a channel on every flush.
+#[derive(Debug, Default)]
+pub(crate) struct FlushNotifier {
+    state: Mutex<FlushNotifierState>,
+}
+
+#[derive(Debug, Default)]
+struct FlushNotifierState {
+    version: u64,
+    closed: bool,
+    wakers: Vec<Waker>,
+}
+
+/// A stream that yields the version of the read-only `Data` of a directed channel after flushes.
+/// If there were multiple flushes since the stream was last polled, it yields only the latest version.
+/// The stream ends when the channel is destroyed.
+///
+/// See [`DirectedChannelPointer::flush_stream`] for more info.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct FlushStream {
+    notifier: Arc<FlushNotifier>,
+    version: u64,
+}
+
+impl FlushNotifier {
+    /// Wake all streams if there are any.
+    pub(crate) fn notify(self: &Arc<Self>, version: u64) {
+        if Arc::strong_count(self) > 1 {
+            let mut state = self.state.lock().unwrap();
+            state.version = version;
+            state.wakers.drain(..).for_each(Waker::wake);
+        }
+    }
+
+    /// End all streams.
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.wakers.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl<Data> DirectedChannelPointer<Data> {
+    /// Get a stream that yields the version of the read-only `Data` after flushes of this channel.
+    /// Any number of streams can exist at the same time.
+    ///
+    /// After receiving a version, a reader can read the new `Data` via its [`ReadOnlyDataPointer`](crate::directed::ReadOnlyDataPointer)
+    /// once it holds a [`DataKey`](crate::DataKey).
+    /// Note that the stream only ends when the channel is destroyed, and not if the channel pointer is just dropped.
+    pub fn flush_stream(&self) -> FlushStream {
+        let notifier = self.flush_notifier().clone();
+        let version = notifier.state.lock().unwrap().version;
+        FlushStream { notifier, version }
+    }
+}
+
+impl Stream for FlushStream {
+    type Item = u64;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<u64>> {
+        let notifier = self.notifier.clone();
+        let mut state = notifier.state.lock().unwrap();
+        if state.version > self.version {
+            self.version = state.version;
+            Poll::Ready(Some(state.version))
+        } else if state.closed {
+            Poll::Ready(None)
+        } else {
+            if !state
+                .wakers
+                .iter()
+                .any(|waker| waker.will_wake(context.waker()))
+            {
+                state.wakers.push(context.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, StreamExt};
+
+    use crate::{directed::DirectedChannel, MasterKey};
+
+    #[test]
+    fn test_flush_stream() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let mut stream1 = channel_pointer.flush_stream();
+        let mut stream2 = channel_pointer.flush_stream();
+
+        for i in 1..=2 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = i * 10;
+            channel_pointer.flush(&master_key.get_channel_key());
+
+            assert_eq!(block_on(stream1.next()), Some(i));
+            assert_eq!(block_on(stream2.next()), Some(i));
+            assert_eq!(
+                *read_only_data_pointer.get(&master_key.get_data_key()),
+                i * 10
+            );
+        }
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+        assert_eq!(block_on(stream1.next()), None);
+        assert_eq!(block_on(stream2.next()), None);
+    }
+
+    #[test]
+    fn test_flush_stream_wakes_waiting_reader() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(0, 0);
+        let mut stream = channel_pointer.flush_stream();
+
+        std::thread::scope(|scope| {
+            let reader = scope.spawn(move || block_on(stream.next()));
+            channel_pointer.flush(&master_key.get_channel_key());
+            assert_eq!(reader.join().unwrap(), Some(1));
+        });
+
+        DirectedChannel::destroy_single(
+            channel_pointer,
+            read_only_data_pointer,
+            writable_data_pointer,
+        );
+    }
+}
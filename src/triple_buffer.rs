@@ -0,0 +1,140 @@
+//! A lock-free triple buffer for a single producer and a single consumer.
+//! Like the [atomic swap channel](crate::atomic_swap), it needs no keys,
+//! but neither the producer nor the consumer ever waits for the other.
+
+use std::{
+    cell::UnsafeCell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Set in [`TripleBuffer::ready`] if the ready buffer was published but not yet consumed.
+const NEW: usize = 4;
+/// Masks the index in [`TripleBuffer::ready`].
+const INDEX: usize = 3;
+
+/// Three buffers shared by a [TripleBufferProducer] and a [TripleBufferConsumer].
+/// At any time, the producer owns the write buffer, the consumer owns the read buffer,
+/// and the ready buffer holds the latest complete `Data` published by the producer.
+///
+/// This is useful if the producer is faster than the consumer, e.g. when a simulation feeds a renderer:
+/// the producer never blocks, and the consumer always gets the latest complete `Data`.
+///
+/// See [TripleBuffer::create] for more info.
+#[derive(Debug)]
+pub struct TripleBuffer<Data> {
+    buffers: [UnsafeCell<Data>; 3],
+    /// The index of the ready buffer, combined with [`NEW`].
+    ready: AtomicUsize,
+}
+
+/// The producing half of a triple buffer.
+#[derive(Debug)]
+pub struct TripleBufferProducer<Data> {
+    buffer: Arc<TripleBuffer<Data>>,
+    write: usize,
+}
+
+/// The consuming half of a triple buffer.
+#[derive(Debug)]
+pub struct TripleBufferConsumer<Data> {
+    buffer: Arc<TripleBuffer<Data>>,
+    read: usize,
+}
+
+impl<Data: Clone> TripleBuffer<Data> {
+    /// Create a triple buffer and hand out its producer and its consumer.
+    /// All three buffers are initialised equally from the given `Data`.
+    /// The buffers are freed when both the producer and the consumer are dropped.
+    pub fn create(data: Data) -> (TripleBufferProducer<Data>, TripleBufferConsumer<Data>) {
+        let buffer = Arc::new(Self {
+            buffers: [
+                UnsafeCell::new(data.clone()),
+                UnsafeCell::new(data.clone()),
+                UnsafeCell::new(data),
+            ],
+            ready: AtomicUsize::new(1),
+        });
+        (
+            TripleBufferProducer {
+                buffer: buffer.clone(),
+                write: 0,
+            },
+            TripleBufferConsumer { buffer, read: 2 },
+        )
+    }
+}
+
+impl<Data> TripleBufferProducer<Data> {
+    /// Publish the given `Data`, such that the consumer gets it on its next call to [TripleBufferConsumer::latest].
+    /// If the consumer did not get the previously published `Data` yet, that `Data` is overwritten eventually.
+    pub fn publish(&mut self, data: Data) {
+        unsafe { *self.buffer.buffers[self.write].get() = data };
+        // Release the written buffer to the consumer, and acquire the buffer it released.
+        let ready = self.buffer.ready.swap(self.write | NEW, Ordering::AcqRel);
+        self.write = ready & INDEX;
+    }
+}
+
+impl<Data> TripleBufferConsumer<Data> {
+    /// Get a reference to the latest `Data` published by the producer.
+    /// Returns the same `Data` as the previous call if nothing was published since.
+    pub fn latest(&mut self) -> &Data {
+        if self.buffer.ready.load(Ordering::Relaxed) & NEW != 0 {
+            // Release the read buffer to the producer, and acquire the published buffer.
+            let ready = self.buffer.ready.swap(self.read, Ordering::AcqRel);
+            self.read = ready & INDEX;
+        }
+        unsafe { &*self.buffer.buffers[self.read].get() }
+    }
+}
+
+unsafe impl<Data: Send + Sync> Send for TripleBufferProducer<Data> {}
+unsafe impl<Data: Send + Sync> Send for TripleBufferConsumer<Data> {}
+
+unsafe impl<Data: Send + Sync> Sync for TripleBufferProducer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for TripleBufferConsumer<Data> {}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        thread,
+    };
+
+    use crate::triple_buffer::TripleBuffer;
+
+    #[test]
+    fn test_fast_producer() {
+        let (mut producer, mut consumer) = TripleBuffer::create([0u64; 16]);
+        let done = AtomicBool::new(false);
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 1..=100_000 {
+                    producer.publish([i; 16]);
+                }
+                done.store(true, Ordering::Release);
+            });
+            scope.spawn(|| {
+                let mut last = 0;
+                let mut frames = 0;
+                while !done.load(Ordering::Acquire) {
+                    let data = consumer.latest();
+                    assert!(data.iter().all(|value| *value == data[0]), "torn read");
+                    assert!(data[0] >= last);
+                    last = data[0];
+                    frames += 1;
+                    // Consume slower than producing.
+                    thread::yield_now();
+                }
+                assert!(frames > 0);
+            });
+        });
+
+        assert_eq!(*consumer.latest(), [100_000; 16]);
+        assert_eq!(*consumer.latest(), [100_000; 16]);
+    }
+}
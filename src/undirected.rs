@@ -2,7 +2,7 @@
 //! Both instances of the transmitted data are readable and writable,
 //! and the data is swapped instead of being sent only in one direction.
 
-use std::mem;
+use std::{mem, ptr};
 
 use crate::{ChannelKey, DataKey};
 
@@ -122,8 +122,8 @@ impl<Data> UndirectedChannel<Data> {
 
             assert!(
                 (channel_data_pointer1 == data_pointer1
-                    && channel_data_pointer2 as *const Data == data_pointer2)
-                    || (channel_data_pointer1 as *const Data == data_pointer2
+                    && ptr::eq(channel_data_pointer2, data_pointer2))
+                    || (ptr::eq(channel_data_pointer1, data_pointer2)
                         && channel_data_pointer2 == data_pointer1)
             );
         }
@@ -208,13 +208,13 @@ impl<Data> Clone for ImmutableUndirectedDataPointer<Data> {
 
 impl<Data> Copy for ImmutableUndirectedDataPointer<Data> {}
 
-unsafe impl<Data> Send for UndirectedChannelPointer<Data> {}
-unsafe impl<Data> Send for UndirectedDataPointer<Data> {}
-unsafe impl<Data> Send for ImmutableUndirectedDataPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for UndirectedChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for UndirectedDataPointer<Data> {}
+unsafe impl<Data: Send + Sync> Send for ImmutableUndirectedDataPointer<Data> {}
 
-unsafe impl<Data> Sync for UndirectedChannelPointer<Data> {}
-unsafe impl<Data> Sync for UndirectedDataPointer<Data> {}
-unsafe impl<Data> Sync for ImmutableUndirectedDataPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for UndirectedChannelPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for UndirectedDataPointer<Data> {}
+unsafe impl<Data: Send + Sync> Sync for ImmutableUndirectedDataPointer<Data> {}
 
 /// Object-safe trait for [`UndirectedChannelPointer`]s.
 pub trait UndirectedSwapChannel: Send + Sync {
@@ -222,7 +222,7 @@ pub trait UndirectedSwapChannel: Send + Sync {
     fn swap(&mut self, channel_key: &ChannelKey);
 }
 
-impl<Data> UndirectedSwapChannel for UndirectedChannelPointer<Data> {
+impl<Data: Send + Sync> UndirectedSwapChannel for UndirectedChannelPointer<Data> {
     fn swap(&mut self, channel_key: &ChannelKey) {
         UndirectedChannelPointer::swap(self, channel_key);
     }
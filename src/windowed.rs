@@ -0,0 +1,132 @@
+//! A windowed two-phase channel.
+//! This is a wrapper around a directed two-phase channel,
+//! whose read-only `Data` is an aggregate over the writable `Data` of the last flushes, such as a moving average.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+};
+
+use crate::{
+    directed::{DirectedChannel, DirectedChannelPointer, ReadOnlyDataPointer, WritableDataPointer},
+    ChannelKey,
+};
+
+/// The function that aggregates the window of a [`WindowedChannel`].
+type Fold<Data> = Box<dyn Fn(&[Data]) -> Data + Send + Sync>;
+
+/// A pointer to a windowed channel, i.e. a directed channel that keeps a window of the writable `Data` of the last flushes,
+/// and whose read-only `Data` is an aggregate over this window.
+/// It can only be accessed using a [ChannelKey].
+///
+/// The readers and the writer of a windowed channel are plain [`ReadOnlyDataPointer`]s and [`WritableDataPointer`]s.
+///
+/// This type should always be destroyed via the [WindowedChannel::destroy] method to ensure soundness (at runtime).
+#[must_use]
+pub struct WindowedChannel<Data> {
+    channel: DirectedChannelPointer<Data>,
+    window: VecDeque<Data>,
+    size: usize,
+    fold: Fold<Data>,
+}
+
+impl<Data: Clone> WindowedChannel<Data> {
+    /// Create a windowed channel over the last `size` flushes, and hand out three pointers to it.
+    /// On every flush, the writable `Data` is pushed into the window, evicting the oldest entry if the window is full,
+    /// and the read-only `Data` is replaced by the result of `fold` over the window, from the oldest to the newest entry.
+    ///
+    /// See [`DirectedChannel::create`] for more info.
+    ///
+    /// **Panics** if the size is zero.
+    pub fn create(
+        size: usize,
+        read_only: Data,
+        writable: Data,
+        fold: impl Fn(&[Data]) -> Data + Send + Sync + 'static,
+    ) -> (Self, ReadOnlyDataPointer<Data>, WritableDataPointer<Data>) {
+        assert!(size > 0, "the size of a window must not be zero");
+        let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+            DirectedChannel::create(read_only, writable);
+        (
+            Self {
+                channel: channel_pointer,
+                window: VecDeque::with_capacity(size),
+                size,
+                fold: Box::new(fold),
+            },
+            read_only_data_pointer,
+            writable_data_pointer,
+        )
+    }
+
+    /// Push the writable `Data` into the window, and replace the read-only `Data` with the aggregate over the window.
+    pub fn flush(&mut self, channel_key: &ChannelKey) {
+        let Self {
+            channel,
+            window,
+            size,
+            fold,
+        } = self;
+        channel.flush_transform(channel_key, |writable| {
+            if window.len() == *size {
+                window.pop_front();
+            }
+            window.push_back(writable.clone());
+            fold(window.make_contiguous())
+        });
+    }
+}
+
+impl<Data> WindowedChannel<Data> {
+    /// The maximum number of entries in the window.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Destroys the windowed channel linked with the given pointers (see [WindowedChannel::create]).
+    /// Returns the read-only and the writable `Data`.
+    ///
+    /// **Panics** if not all pointers point to the same channel.
+    pub fn destroy(
+        self,
+        read_only_data_pointers: impl IntoIterator<Item = ReadOnlyDataPointer<Data>>,
+        writable_data_pointer: WritableDataPointer<Data>,
+    ) -> (Data, Data) {
+        self.channel
+            .destroy(read_only_data_pointers, writable_data_pointer)
+    }
+}
+
+impl<Data: Debug> Debug for WindowedChannel<Data> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("WindowedChannel")
+            .field("channel", &self.channel)
+            .field("window", &self.window)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{windowed::WindowedChannel, MasterKey};
+
+    #[test]
+    fn test_moving_sum() {
+        let mut master_key = unsafe { MasterKey::create_unlimited() };
+        let (mut channel, read_only_data_pointer, mut writable_data_pointer) =
+            WindowedChannel::create(3, 0, 0, |window: &[i32]| window.iter().sum());
+        assert_eq!(channel.size(), 3);
+
+        let mut sums = Vec::new();
+        for value in 1..=5 {
+            *writable_data_pointer.get_mut(&master_key.get_data_key()) = value;
+            channel.flush(&master_key.get_channel_key());
+            sums.push(*read_only_data_pointer.get(&master_key.get_data_key()));
+        }
+        assert_eq!(sums, [1, 3, 6, 9, 12]);
+
+        channel.destroy([read_only_data_pointer], writable_data_pointer);
+    }
+}
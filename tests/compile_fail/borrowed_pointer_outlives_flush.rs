@@ -0,0 +1,11 @@
+use two_phase_channel::{directed::DirectedChannel, MasterKey};
+
+fn main() {
+    let mut master_key = unsafe { MasterKey::create_unlimited() };
+    let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+        DirectedChannel::create(0, 0);
+    let (borrowed_read_only_data_pointer, _) = channel_pointer.borrow_pointers(&mut writable_data_pointer);
+    channel_pointer.flush(&master_key.get_channel_key());
+    borrowed_read_only_data_pointer.get(&master_key.get_data_key());
+    DirectedChannel::destroy_single(channel_pointer, read_only_data_pointer, writable_data_pointer);
+}
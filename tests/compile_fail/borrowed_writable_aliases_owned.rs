@@ -0,0 +1,14 @@
+use two_phase_channel::{directed::DirectedChannel, MasterKey};
+
+fn main() {
+    let mut master_key = unsafe { MasterKey::create_unlimited() };
+    let (mut channel_pointer, read_only_data_pointer, mut writable_data_pointer) =
+        DirectedChannel::create(0, 0);
+    let (_, mut borrowed_writable_data_pointer) =
+        channel_pointer.borrow_pointers(&mut writable_data_pointer);
+    let data_key = master_key.get_data_key();
+    let writable = writable_data_pointer.get_mut(&data_key);
+    *borrowed_writable_data_pointer.get_mut(&data_key) = 1;
+    *writable = 2;
+    DirectedChannel::destroy_single(channel_pointer, read_only_data_pointer, writable_data_pointer);
+}
@@ -0,0 +1,7 @@
+use two_phase_channel::MasterKey;
+
+fn main() {
+    let master_key = MasterKey::create();
+    drop(master_key);
+    drop(master_key);
+}
@@ -0,0 +1,12 @@
+use std::rc::Rc;
+
+use two_phase_channel::directed::DirectedChannel;
+
+fn assert_send<T: Send>(_: &T) {}
+
+fn main() {
+    let (channel_pointer, read_only_data_pointer, writable_data_pointer) =
+        DirectedChannel::create(Rc::new(0), Rc::new(0));
+    assert_send(&read_only_data_pointer);
+    DirectedChannel::destroy_single(channel_pointer, read_only_data_pointer, writable_data_pointer);
+}
@@ -0,0 +1,152 @@
+//! Derive macros for [`two_phase_channel`](https://docs.rs/two_phase_channel).
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields};
+
+/// Derive per-field directed channels for a struct with named fields.
+///
+/// For a struct `State { a: A, b: B }`, this generates three types:
+///  * `StateChannels { a: DirectedChannelPointer<A>, b: DirectedChannelPointer<B> }`, which can create, flush and destroy all channels at once,
+///  * `StateReader { a: ReadOnlyDataPointer<A>, b: ReadOnlyDataPointer<B> }`, and
+///  * `StateWriter { a: WritableDataPointer<A>, b: WritableDataPointer<B> }`.
+///
+/// Since each field has its own channel, the pointers of different fields can be moved to different threads independently.
+#[proc_macro_derive(SplitChannels)]
+pub fn derive_split_channels(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    split_channels(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn split_channels(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input,
+                    "SplitChannels can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input,
+                "SplitChannels can only be derived for structs",
+            ))
+        }
+    };
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let channels = format_ident!("{}Channels", name);
+    let reader = format_ident!("{}Reader", name);
+    let writer = format_ident!("{}Writer", name);
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let generics = &input.generics;
+
+    let field_vis: Vec<_> = fields.iter().map(|field| &field.vis).collect();
+    let field_names: Vec<_> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+    let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let read_only_names: Vec<_> = field_names
+        .iter()
+        .map(|field_name| format_ident!("{}_read_only", field_name))
+        .collect();
+    let writable_names: Vec<_> = field_names
+        .iter()
+        .map(|field_name| format_ident!("{}_writable", field_name))
+        .collect();
+    let mut clone_generics = input.generics.clone();
+    let clone_where_clause = clone_generics.make_where_clause();
+    for field_type in &field_types {
+        clone_where_clause
+            .predicates
+            .push(parse_quote! { #field_type: ::core::clone::Clone });
+    }
+    let where_clone = &clone_generics.where_clause;
+    let mut directed_channel_generics = clone_generics.clone();
+    let directed_channel_where_clause = directed_channel_generics.make_where_clause();
+    for field_type in &field_types {
+        directed_channel_where_clause
+            .predicates
+            .push(parse_quote! { #field_type: ::core::marker::Send + ::core::marker::Sync });
+    }
+    let where_directed_channel = &directed_channel_generics.where_clause;
+
+    let channels_doc = format!("The directed channels of the fields of [`{}`].", name);
+    let reader_doc = format!("The read-only data pointers of the fields of [`{}`].", name);
+    let writer_doc = format!("The writable data pointers of the fields of [`{}`].", name);
+
+    Ok(quote! {
+        #[doc = #channels_doc]
+        #[derive(Debug)]
+        #[must_use]
+        #vis struct #channels #generics #where_clause {
+            #(#field_vis #field_names: ::two_phase_channel::directed::DirectedChannelPointer<#field_types>,)*
+        }
+
+        #[doc = #reader_doc]
+        #[derive(Debug)]
+        #[must_use]
+        #vis struct #reader #generics #where_clause {
+            #(#field_vis #field_names: ::two_phase_channel::directed::ReadOnlyDataPointer<#field_types>,)*
+        }
+
+        #[doc = #writer_doc]
+        #[derive(Debug)]
+        #[must_use]
+        #vis struct #writer #generics #where_clause {
+            #(#field_vis #field_names: ::two_phase_channel::directed::WritableDataPointer<#field_types>,)*
+        }
+
+        impl #impl_generics #channels #type_generics #where_clause {
+            /// Create one directed channel per field and hand out the pointers to them.
+            ///
+            /// See [`DirectedChannel::create`](::two_phase_channel::directed::DirectedChannel::create) for more info.
+            #vis fn create(read_only: #name #type_generics, writable: #name #type_generics) -> (Self, #reader #type_generics, #writer #type_generics) {
+                let #name { #(#field_names: #read_only_names,)* } = read_only;
+                let #name { #(#field_names: #writable_names,)* } = writable;
+                #(
+                    let #field_names = ::two_phase_channel::directed::DirectedChannel::create(#read_only_names, #writable_names);
+                )*
+                (
+                    Self { #(#field_names: #field_names.0,)* },
+                    #reader { #(#field_names: #field_names.1,)* },
+                    #writer { #(#field_names: #field_names.2,)* },
+                )
+            }
+
+            /// Destroy all directed channels linked with the given pointers (see `create`).
+            /// Returns the read-only and the writable data.
+            ///
+            /// **Panics** if not all pointers point to the same channels.
+            #vis fn destroy(self, reader: #reader #type_generics, writer: #writer #type_generics) -> (#name #type_generics, #name #type_generics) {
+                #(
+                    let (#read_only_names, #writable_names) = ::two_phase_channel::directed::DirectedChannel::destroy_single(self.#field_names, reader.#field_names, writer.#field_names);
+                )*
+                (
+                    #name { #(#field_names: #read_only_names,)* },
+                    #name { #(#field_names: #writable_names,)* },
+                )
+            }
+        }
+
+        impl #impl_generics #channels #type_generics #where_clone {
+            /// Flush the directed channels of all fields.
+            #vis fn flush(&mut self, channel_key: &::two_phase_channel::ChannelKey) {
+                #(self.#field_names.flush(channel_key);)*
+            }
+        }
+
+        impl #impl_generics ::two_phase_channel::directed::IDirectedChannel for #channels #type_generics #where_directed_channel {
+            fn flush(&mut self, channel_key: &::two_phase_channel::ChannelKey) {
+                #channels::flush(self, channel_key);
+            }
+        }
+    })
+}
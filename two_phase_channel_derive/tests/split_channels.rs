@@ -0,0 +1,67 @@
+use two_phase_channel::{directed::IDirectedChannel, MasterKey};
+use two_phase_channel_derive::SplitChannels;
+
+#[derive(Debug, Clone, PartialEq, SplitChannels)]
+struct State {
+    position: (i32, i32),
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, SplitChannels)]
+struct Generic<T> {
+    value: T,
+}
+
+#[test]
+fn test() {
+    let mut master_key = unsafe { MasterKey::create_unlimited() };
+    let initial = State {
+        position: (0, 0),
+        name: String::new(),
+    };
+    let (mut channels, reader, mut writer) = StateChannels::create(initial.clone(), initial);
+
+    let data_key = master_key.get_data_key();
+    *writer.position.get_mut(&data_key) = (1, 2);
+    assert_eq!(*reader.position.get(&data_key), (0, 0));
+    assert_eq!(*reader.name.get(&data_key), "");
+
+    let channel_key = data_key.into_channel_key();
+    channels.flush(&channel_key);
+
+    let data_key = channel_key.into_data_key();
+    assert_eq!(*reader.position.get(&data_key), (1, 2));
+    assert_eq!(*reader.name.get(&data_key), "");
+    writer.name.get_mut(&data_key).push_str("state");
+
+    let channel_key = data_key.into_channel_key();
+    let dyn_channels: &mut dyn IDirectedChannel = &mut channels;
+    dyn_channels.flush(&channel_key);
+
+    let data_key = channel_key.into_data_key();
+    assert_eq!(*reader.position.get(&data_key), (1, 2));
+    assert_eq!(*reader.name.get(&data_key), "state");
+
+    let (read_only, writable) = channels.destroy(reader, writer);
+    let expected = State {
+        position: (1, 2),
+        name: "state".to_string(),
+    };
+    assert_eq!(read_only, expected);
+    assert_eq!(writable, expected);
+}
+
+#[test]
+fn test_generic() {
+    let mut master_key = unsafe { MasterKey::create_unlimited() };
+    let (mut channels, reader, mut writer) =
+        GenericChannels::create(Generic { value: 0 }, Generic { value: 1 });
+
+    channels.flush(&master_key.get_channel_key());
+    assert_eq!(*reader.value.get(&master_key.get_data_key()), 1);
+    *writer.value.get_mut(&master_key.get_data_key()) = 2;
+
+    let (read_only, writable) = channels.destroy(reader, writer);
+    assert_eq!(read_only, Generic { value: 1 });
+    assert_eq!(writable, Generic { value: 2 });
+}